@@ -0,0 +1,119 @@
+//! A custom frame: a logo bouncing off the edges of the screen in front of
+//! the snow, changing color on every bounce.
+//!
+//! Run it with `cargo run --example bouncing_logo`.
+
+#![allow(clippy::needless_return)]
+
+use std::{thread, time::Duration};
+
+use colored::Color;
+use cyber_christmas_card::{
+    string_to_content_vec, Bounds, Content, Frame, Printer, Scene, SnowFrame, StringWidth,
+};
+
+const LOGO: &str = "[ 聖誕 DVD ]";
+const COLORS: [Color; 4] = [Color::Red, Color::Green, Color::Yellow, Color::Cyan];
+const FRAMES: usize = 200;
+
+struct BouncingLogoFrame {
+    frame_width: usize,
+    frame_height: usize,
+    x: usize,
+    y: usize,
+    // columns and rows moved by on each tick
+    dx: isize,
+    dy: isize,
+    bounces: usize,
+    // one content per column of the logo, wide glyphs taking two
+    cells: Vec<Content>,
+}
+
+impl BouncingLogoFrame {
+    fn new() -> Self {
+        let mut frame = BouncingLogoFrame {
+            frame_width: 0,
+            frame_height: 0,
+            x: 0,
+            y: 0,
+            dx: 1,
+            dy: 1,
+            bounces: 0,
+            cells: Vec::new(),
+        };
+        frame.paint();
+        return frame;
+    }
+
+    fn paint(&mut self) {
+        self.cells = string_to_content_vec(LOGO, COLORS[self.bounces % COLORS.len()]);
+    }
+
+    // moves `position` a step along `speed` within `0..=last`, turning back at
+    // either end; whether it bounced
+    fn step(position: &mut usize, speed: &mut isize, last: usize) -> bool {
+        let next = *position as isize + *speed;
+        if next < 0 || next > last as isize {
+            *speed = -*speed;
+            *position = (*position as isize + *speed).clamp(0, last as isize) as usize;
+            return true;
+        }
+        *position = next as usize;
+        return false;
+    }
+}
+
+impl Frame for BouncingLogoFrame {
+    fn update(&mut self, screen_width: usize, screen_height: usize) {
+        self.frame_width = screen_width;
+        self.frame_height = screen_height;
+    }
+
+    fn tick(&mut self, scene: &Scene) {
+        // the scene is the size the printer gave every frame this update
+        let last_x = scene.width().saturating_sub(LOGO.width());
+        let last_y = scene.height().saturating_sub(1);
+        let bounced_x = Self::step(&mut self.x, &mut self.dx, last_x);
+        let bounced_y = Self::step(&mut self.y, &mut self.dy, last_y);
+        if bounced_x || bounced_y {
+            self.bounces += 1;
+            self.paint();
+        }
+    }
+
+    fn content_bounds(&self) -> Option<Bounds> {
+        return Some(Bounds {
+            x: self.x,
+            y: self.y,
+            width: self.cells.len(),
+            height: 1,
+        });
+    }
+
+    fn get_content(&mut self, x: usize, y: usize) -> Content {
+        if y != self.y || x < self.x {
+            return Content::Transparent;
+        }
+        return self
+            .cells
+            .get(x - self.x)
+            .cloned()
+            .unwrap_or(Content::Transparent);
+    }
+}
+
+fn main() {
+    let mut printer = Printer::new(vec![
+        Box::new(BouncingLogoFrame::new()),
+        Box::<SnowFrame>::default(),
+    ]);
+    printer.scene_mut().wind = 1;
+
+    for _ in 0..FRAMES {
+        printer.update();
+        printer.clear();
+        printer.print();
+        thread::sleep(Duration::from_millis(80));
+    }
+    printer.clear();
+}
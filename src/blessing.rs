@@ -0,0 +1,24 @@
+/// Blessing shown under the tree, `{year}` being replaced by the year.
+pub const DEFAULT_BLESSING_TEMPLATE: &str = "{year} 聖誕快樂";
+
+/// Blessing templates by language code, `{year}` being replaced by the year.
+pub const BLESSINGS: &[(&str, &str)] = &[
+    ("zh-Hant", DEFAULT_BLESSING_TEMPLATE),
+    ("zh-Hans", "{year} 圣诞快乐"),
+    ("en", "Merry Christmas {year}"),
+    ("ja", "メリークリスマス {year}"),
+    ("ko", "메리 크리스마스 {year}"),
+    ("es", "¡Feliz Navidad {year}!"),
+    ("fr", "Joyeux Noël {year}"),
+    ("de", "Frohe Weihnachten {year}"),
+    ("it", "Buon Natale {year}"),
+    ("pt", "Feliz Natal {year}"),
+];
+
+/// The bundled blessing template for a language code, if there is one.
+pub fn blessing_template(lang: &str) -> Option<&'static str> {
+    return BLESSINGS
+        .iter()
+        .find(|(code, _)| code.eq_ignore_ascii_case(lang))
+        .map(|(_, template)| *template);
+}
@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+use crate::cli::Downgrade;
+
+// frames taking under this fraction of the budget leave time to spare
+const HEADROOM: f32 = 0.5;
+// frames in a row with time to spare before the last downgrade is undone
+const CALM_FRAMES: usize = 10;
+
+/// A change to how much work drawing a frame takes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Adjustment {
+    Take(Downgrade),
+    Undo(Downgrade),
+}
+
+/// Keeps drawing a frame within a time budget, taking the next downgrade
+/// of its order after every frame that runs over, and undoing the last one
+/// taken once frames have had time to spare for a while.
+pub struct FrameBudget {
+    max: Duration,
+    order: Vec<Downgrade>,
+    // downgrades of the order taken so far
+    taken: usize,
+    // frames in a row that had time to spare
+    calm: usize,
+}
+
+impl FrameBudget {
+    pub fn new(max: Duration, order: Vec<Downgrade>) -> Self {
+        return FrameBudget {
+            max,
+            order,
+            taken: 0,
+            calm: 0,
+        };
+    }
+
+    /// Records that the last frame took `took` to draw, returning what to
+    /// change about the next one, if anything.
+    pub fn record(&mut self, took: Duration) -> Option<Adjustment> {
+        if took > self.max {
+            self.calm = 0;
+            let downgrade = self.order.get(self.taken)?.clone();
+            self.taken += 1;
+            return Some(Adjustment::Take(downgrade));
+        }
+
+        if took.as_secs_f32() >= self.max.as_secs_f32() * HEADROOM || self.taken == 0 {
+            self.calm = 0;
+            return None;
+        }
+        self.calm += 1;
+        if self.calm < CALM_FRAMES {
+            return None;
+        }
+
+        self.calm = 0;
+        self.taken -= 1;
+        return Some(Adjustment::Undo(self.order[self.taken].clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn downgrades_in_order_and_recovers_with_time_to_spare() {
+        let mut budget = FrameBudget::new(
+            Duration::from_millis(10),
+            vec![Downgrade::Layer("aurora".to_owned()), Downgrade::Snowfall],
+        );
+        let slow = Duration::from_millis(15);
+        let fast = Duration::from_millis(2);
+        let aurora = Downgrade::Layer("aurora".to_owned());
+
+        assert_eq!(budget.record(slow), Some(Adjustment::Take(aurora.clone())));
+        assert_eq!(
+            budget.record(slow),
+            Some(Adjustment::Take(Downgrade::Snowfall))
+        );
+        // nothing left to cut back on
+        assert_eq!(budget.record(slow), None);
+
+        // a frame close to the budget starts the wait over
+        for _ in 0..CALM_FRAMES - 1 {
+            assert_eq!(budget.record(fast), None);
+        }
+        assert_eq!(budget.record(Duration::from_millis(8)), None);
+        for _ in 0..CALM_FRAMES - 1 {
+            assert_eq!(budget.record(fast), None);
+        }
+        assert_eq!(
+            budget.record(fast),
+            Some(Adjustment::Undo(Downgrade::Snowfall))
+        );
+        for _ in 0..CALM_FRAMES - 1 {
+            assert_eq!(budget.record(fast), None);
+        }
+        assert_eq!(budget.record(fast), Some(Adjustment::Undo(aurora)));
+        for _ in 0..2 * CALM_FRAMES {
+            assert_eq!(budget.record(fast), None);
+        }
+    }
+}
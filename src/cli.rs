@@ -0,0 +1,1068 @@
+use std::{
+    env, fmt,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use colored::Color;
+
+use crate::config;
+use cyber_christmas_card::{
+    blessing_template, char_width, color, frame_names, BlessingAlign, Date, TreeShape, BLESSINGS,
+    DEFAULT_ORNAMENT_DENSITY, DEFAULT_SLEIGH_INTERVAL, DEFAULT_WINDOW_LEDGE,
+};
+
+pub const USAGE: &str = "\
+Usage: cyber-christmas-card [OPTIONS]
+
+Options:
+      --config <PATH>               Load options from a TOML file, flags on the command line win
+      --scene <NAME>                Scene to show, christmas or newyear-countdown [default: christmas]
+      --ornament-density <0.0-1.0>  Chance of a leaf being an ornament [default: 0.09]
+      --tree-shape <SHAPE>          Outline of the tree, triangle, tiered or rounded [default: triangle]
+      --lang <CODE>                 Language of the blessing, one of zh-Hant, zh-Hans, en, ja, ko, es, fr, de, it, pt [default: zh-Hant]
+      --message <TEXT>              Show this instead of the blessing, {year} being the year. Repeat to rotate through several
+      --message-interval <SECONDS>  Seconds each of several messages is shown for [default: 5]
+      --no-blessing                 Show just the tree, without the blessing under it
+      --blessing-align <ALIGN>      Where the blessing sits on its row, left, center or right [default: center]
+      --blessing-offset <COLUMNS>   Columns to move the blessing right of where it is aligned, negative moves it left [default: 0]
+      --typewriter                  Type the blessing out a character per frame behind a blinking caret
+      --to <NAME>                   Address the card to NAME on a line near the bottom
+      --from <NAME>                 Sign the card from NAME on the line with --to
+      --message-fade                Fade each of several messages in as it takes its turn
+      --first-snow                  Greet with First snow! instead on the first run of the day
+      --first-snow-state <PATH>     File remembering the day of the last run, implies --first-snow [default: $XDG_STATE_HOME/cyber-christmas-card/last-run]
+      --year <YEAR>                 Year to wish a merry christmas for [default: this year]
+      --wind <COLUMNS>              Columns the wind blows snow per update, negative blows left [default: 0]
+      --snow-speed <MIN[-MAX]>      Rows a flake falls per update, each flake falling at its own speed within MIN-MAX [default: 1]
+      --snow-layers <N>             Spread the snow behind the tree over N layers, each further one falling slower with thinner, dimmer and smaller flakes [default: 1]
+      --layered-snow                Split the snow into a layer behind the tree and a sparse, faster one falling in front of it
+      --sparkle                     Let the odd flake twinkle partway down and fade away
+      --snow-prefill                Start with the sky already full of falling snow [default: on]
+      --no-snow-prefill             Start with an empty sky instead of one already full of falling snow
+      --ground-snow                 Let snow pile up on the ground
+      --drift                       Let the wind pile ground snow into drifts, implies --ground-snow
+      --reflections                 Tint the ground snow with the lights above it, with --ground-snow
+      --snow-caps                   Let snow settle on the branches and the blessing
+      --smooth-tree                 Soften the jagged edges of the tree with dimmer leaves
+      --lights-charge               Power the tree's lights on in a wave from the bottom up before they twinkle
+      --charge-seconds <SECONDS>    Seconds the lights of --lights-charge take to come on, implies it [default: 2]
+      --candles                     Light a pair of candles beside the tree
+      --clouds                      Drift a few clouds across the top of the sky with the wind
+      --snow-from-clouds            Let the snow fall only from under the clouds, implies --clouds
+      --aurora                      Wave northern lights across the top of the sky
+      --aurora-wind                 Let the --wind carry the aurora's waves along, implies --aurora
+      --gifts                       Lay a few wrapped presents on the ground under the tree
+      --window                      Look out at the card through a window, snow building up on its sill
+      --window-ledge <ROWS>         Rows of snow the window sill takes before further flakes melt into it, implies --window [default: 3]
+      --snowman                     Stand a snowman on the ground beside the tree
+      --snowman-melt <MINUTES>      Melt the snowman down to a puddle over this many minutes, implies --snowman [default: never]
+      --snowman-reset               Build the snowman up again once melted, for the melt to start over
+      --vignette <0.0-1.0>          Darken the scene away from candle light [default: 0.0]
+      --sleigh                      Let santa fly his sleigh across the sky
+      --sleigh-interval <FRAMES>    Frames between two flights of the sleigh [default: 60]
+      --no-sleigh-bubble            Let santa fly by without calling out Ho Ho Ho!
+      --scroll-speed <COLUMNS>      Columns the scene scrolls sideways per update [default: 0]
+      --parallax <LAYER=FACTOR>     Scroll tree, garland, sleigh, candles, snowman, gifts, snow, aurora or clouds at this fraction of --scroll-speed, repeatable
+      --pan <COLUMNS>               Columns the camera pans across the world per update [default: 0]
+      --pan-direction <DIRECTION>   Direction the camera pans in, left or right [default: right]
+      --world-width <COLUMNS>       Width of the world the camera pans across [default: the screen's]
+      --garland                     String a garland of bulbs across the world
+      --add-frame <NAME>            Add a frame by name under the snow, one of tree, snow, garland, sleigh, candles, snowman, gifts, aurora, clouds, window, fireworks or debug, repeatable
+      --seed <SEED>                 Seed every frame for a reproducible card [default: random]
+      --seed-text <TEXT>            Seed every frame with a word or phrase that is easy to share, --seed winning over it
+      --tree-seed <SEED>            Seed only the tree's ornaments, overriding --seed
+      --date-seed                   Seed the tree's ornaments with today's date, holding them still so they change from day to day only
+      --snow-seed <SEED>            Seed only the snowfall, overriding --seed
+      --size <WxH>                  Render at this size instead of the terminal's
+      --fullscreen                  Grow the tree to fill the terminal, ignoring --max-width
+      --autoscale                   Shrink the tree to fit a terminal too small for it instead of cutting it off
+      --compact                     Keep the scene to a modest 60x20 in the middle of the terminal
+      --max-width <COLUMNS>         Cap the width of the scene, centring it between blank margins when there is more room [default: no cap]
+      --region <X,Y,W,H>            Draw only within this rectangle of the terminal [default: all of it]
+      --fill-char <CHAR>            Character drawn where nothing else is, e.g. . for a starfield [default: space]
+      --fill-color <COLOR>          Color of --fill-char, a name like blue or bright black or a #rrggbb code [default: the terminal's]
+      --title <TITLE>               Terminal window title while running, empty to leave it alone [default: 🎄 Merry Christmas 🎄]
+      --frames-dir <DIR>            Write each of the --frames frames to a numbered image in DIR instead of the terminal, needs the image feature
+      --frames-format <FORMAT>      Format of the images in --frames-dir, png or ppm [default: png]
+      --precompute <FRAMES>         Render this many frames up front, then loop them doing no further work. Costs memory, about 10 to 20 bytes per cell per frame
+      --replay <PATH>               Loop an asciicast v2 recording instead of drawing the card
+      --bpm <BPM>                   Blink the ornaments and chase the garland to music at this tempo, best with an --interval shorter than a beat [default: off]
+      --render-mode <MODE>          How frames are drawn: clear clears the screen first, overwrite draws over the last frame, diff draws only what changed [default: diff]
+      --interval <MS>               Milliseconds between two frames [default: 1000]
+      --max-frame-ms <MS>           Cut back on the effects while drawing a frame takes longer than this, bringing them back once there is time to spare [default: off]
+      --downgrade <STEPS>           What --max-frame-ms cuts back on first to last, comma separated: layers to hide, vignette to turn off or snowfall to halve the snow [default: aurora,vignette,snowfall]
+      --frames <COUNT>              Exit after showing this many frames [default: run until q is pressed]
+      --no-fade                     Exit instantly instead of fading the card out
+      --kiosk                       Run as a screensaver, exiting instantly on any key press or mouse movement
+      --focus-reactive              Thicken the snow while the terminal has focus and calm it while it has not, where the terminal reports focus
+      --control-stdin               Read changes to the message, wind, snowfall, bpm, scroll, pan and shown layers from stdin as JSON lines while the card runs
+      --reverse-layers              Stack the layers the other way up, the snow at the bottom and the tree on top
+      --save-config <PATH>          Write the options to a config file for --config to show the same card again, then exit
+      --dry-run                     Check the options and build every frame, then exit without drawing anything
+      --debug                       Overlay the wind and the depth of the ground snow of every column
+      --debug-overlap               Highlight in magenta every cell more than one layer draws at
+      --no-catch-panics             Let a layer that panics bring the card down instead of leaving it out
+  -h, --help                        Print help
+";
+
+const DEFAULT_TITLE: &str = "🎄 Merry Christmas 🎄";
+const DEFAULT_INTERVAL: u64 = 1000;
+const DEFAULT_LANG: &str = "zh-Hant";
+
+// ranges of the options taking any number, kept to on the command line as in
+// a config file so any options can be saved and loaded back
+pub const SCROLL_SPEED_RANGE: RangeInclusive<f64> = -100.0..=100.0;
+pub const BPM_RANGE: RangeInclusive<f64> = 1.0..=1000.0;
+pub const MESSAGE_INTERVAL_RANGE: RangeInclusive<f64> = 0.1..=3600.0;
+pub const PAN_RANGE: RangeInclusive<f64> = 0.0..=100.0;
+pub const ORNAMENT_DENSITY_RANGE: RangeInclusive<f64> = 0.0..=1.0;
+pub const CHARGE_SECONDS_RANGE: RangeInclusive<f64> = 0.1..=60.0;
+pub const SNOWMAN_MELT_RANGE: RangeInclusive<f64> = 0.1..=100_000.0;
+pub const VIGNETTE_RANGE: RangeInclusive<f64> = 0.0..=1.0;
+pub const PARALLAX_RANGE: RangeInclusive<f64> = -10.0..=10.0;
+
+#[derive(Debug, PartialEq)]
+pub struct Options {
+    pub ornament_density: f64,
+    pub tree_shape: TreeShape,
+    pub scene: Option<SceneKind>,
+    pub lang: String,
+    pub year: Option<i32>,
+    pub wind: isize,
+    pub snow_speed: SpeedRange,
+    pub snow_prefill: bool,
+    pub sparkle: bool,
+    pub snow_layers: usize,
+    pub layered_snow: bool,
+    pub ground_snow: bool,
+    pub drift: bool,
+    pub reflections: bool,
+    pub snow_caps: bool,
+    pub smooth_tree: bool,
+    pub lights_charge: bool,
+    pub lights_charge_seconds: f32,
+    pub candles: bool,
+    pub aurora: bool,
+    pub aurora_wind: bool,
+    pub gifts: bool,
+    pub clouds: bool,
+    pub snow_from_clouds: bool,
+    pub window: bool,
+    pub window_ledge: usize,
+    pub snowman: bool,
+    pub snowman_melt: Option<f32>,
+    pub snowman_reset: bool,
+    pub vignette: f32,
+    pub sleigh: bool,
+    pub sleigh_interval: usize,
+    pub sleigh_bubble: bool,
+    pub scroll_speed: f32,
+    pub parallax: Vec<LayerParallax>,
+    pub pan: f32,
+    pub pan_direction: PanDirection,
+    pub world_width: Option<usize>,
+    pub garland: bool,
+    pub seed: Option<u64>,
+    pub seed_text: Option<String>,
+    pub tree_seed: Option<u64>,
+    pub date_seed: bool,
+    pub snow_seed: Option<u64>,
+    pub size: Option<Size>,
+    pub region: Option<Region>,
+    pub max_width: Option<usize>,
+    pub layout: Layout,
+    pub title: String,
+    pub fill_char: FillChar,
+    pub fill_color: Option<FillColor>,
+    pub interval: u64,
+    pub max_frame_ms: Option<u64>,
+    pub downgrade: DowngradeOrder,
+    pub bpm: Option<f32>,
+    pub messages: Vec<String>,
+    pub message_interval: f32,
+    pub message_fade: bool,
+    pub blessing: bool,
+    pub blessing_align: BlessingAlign,
+    pub blessing_offset: isize,
+    pub typewriter: bool,
+    pub to: Option<String>,
+    pub from: Option<String>,
+    pub first_snow: bool,
+    pub first_snow_state: Option<String>,
+    pub frames_dir: Option<String>,
+    pub frames_format: ImageFormat,
+    pub render_mode: RenderMode,
+    pub precompute: Option<usize>,
+    pub replay: Option<String>,
+    pub frames: Option<u64>,
+    pub fade: bool,
+    pub autoscale: bool,
+    pub kiosk: bool,
+    pub dry_run: bool,
+    pub save_config: Option<String>,
+    pub focus_reactive: bool,
+    pub control_stdin: bool,
+    pub reverse_layers: bool,
+    pub extra_frames: Vec<String>,
+    pub debug: bool,
+    pub debug_overlap: bool,
+    pub catch_panics: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        return Options {
+            ornament_density: DEFAULT_ORNAMENT_DENSITY,
+            tree_shape: TreeShape::Triangle,
+            scene: None,
+            lang: DEFAULT_LANG.to_owned(),
+            year: None,
+            wind: 0,
+            snow_speed: SpeedRange { min: 1.0, max: 1.0 },
+            snow_prefill: true,
+            sparkle: false,
+            snow_layers: 1,
+            layered_snow: false,
+            ground_snow: false,
+            drift: false,
+            reflections: false,
+            snow_caps: false,
+            smooth_tree: false,
+            lights_charge: false,
+            lights_charge_seconds: 2.0,
+            candles: false,
+            aurora: false,
+            aurora_wind: false,
+            gifts: false,
+            clouds: false,
+            snow_from_clouds: false,
+            window: false,
+            window_ledge: DEFAULT_WINDOW_LEDGE,
+            snowman: false,
+            snowman_melt: None,
+            snowman_reset: false,
+            vignette: 0.0,
+            sleigh: false,
+            sleigh_interval: DEFAULT_SLEIGH_INTERVAL,
+            sleigh_bubble: true,
+            scroll_speed: 0.0,
+            parallax: Vec::new(),
+            pan: 0.0,
+            pan_direction: PanDirection::Right,
+            world_width: None,
+            garland: false,
+            seed: None,
+            seed_text: None,
+            tree_seed: None,
+            date_seed: false,
+            snow_seed: None,
+            size: None,
+            region: None,
+            max_width: None,
+            layout: Layout::Auto,
+            title: DEFAULT_TITLE.to_owned(),
+            fill_char: FillChar(' '),
+            fill_color: None,
+            interval: DEFAULT_INTERVAL,
+            max_frame_ms: None,
+            downgrade: DowngradeOrder(vec![
+                Downgrade::Layer("aurora".to_owned()),
+                Downgrade::Vignette,
+                Downgrade::Snowfall,
+            ]),
+            bpm: None,
+            messages: Vec::new(),
+            message_interval: 5.0,
+            message_fade: false,
+            blessing: true,
+            blessing_align: BlessingAlign::Center,
+            blessing_offset: 0,
+            typewriter: false,
+            to: None,
+            from: None,
+            first_snow: false,
+            first_snow_state: None,
+            frames_dir: None,
+            frames_format: ImageFormat::Png,
+            render_mode: RenderMode::Diff,
+            precompute: None,
+            replay: None,
+            frames: None,
+            fade: true,
+            autoscale: false,
+            kiosk: false,
+            dry_run: false,
+            save_config: None,
+            focus_reactive: false,
+            control_stdin: false,
+            reverse_layers: false,
+            extra_frames: Vec::new(),
+            debug: false,
+            debug_overlap: false,
+            catch_panics: true,
+        };
+    }
+}
+
+/// Dimensions of the card, in columns and rows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Size {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl FromStr for Size {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((width, height)) = s.split_once('x') else {
+            return Err("expected WIDTHxHEIGHT".to_owned());
+        };
+        let width = width.trim().parse().map_err(|_| "invalid width")?;
+        let height = height.trim().parse().map_err(|_| "invalid height")?;
+        if width == 0 || height == 0 {
+            return Err("the size must not be empty".to_owned());
+        }
+
+        return Ok(Size { width, height });
+    }
+}
+
+impl fmt::Display for Size {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f, "{}x{}", self.width, self.height);
+    }
+}
+
+/// Layers of the card that can be given a parallax, top-most first.
+pub const LAYERS: &[&str] = &[
+    "tree", "garland", "sleigh", "candles", "snowman", "gifts", "snow", "aurora", "clouds",
+];
+
+/// How fast a layer scrolls, as a fraction of the scroll speed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayerParallax {
+    pub layer: String,
+    pub factor: f32,
+}
+
+impl FromStr for LayerParallax {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((layer, factor)) = s.split_once('=') else {
+            return Err("expected LAYER=FACTOR".to_owned());
+        };
+        if !LAYERS.contains(&layer) {
+            return Err(format!(
+                "unknown layer '{}', expected one of {}",
+                layer,
+                LAYERS.join(", ")
+            ));
+        }
+        let factor = factor.trim().parse().map_err(|_| "invalid factor")?;
+
+        return Ok(LayerParallax {
+            layer: layer.to_owned(),
+            factor,
+        });
+    }
+}
+
+/// What the card shows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SceneKind {
+    Christmas,
+    /// A countdown to midnight, then fireworks and a happy new year.
+    NewYearCountdown,
+}
+
+impl FromStr for SceneKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s {
+            "christmas" => Ok(SceneKind::Christmas),
+            "newyear-countdown" => Ok(SceneKind::NewYearCountdown),
+            _ => Err("expected christmas or newyear-countdown".to_owned()),
+        };
+    }
+}
+
+impl fmt::Display for SceneKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return f.write_str(match self {
+            SceneKind::Christmas => "christmas",
+            SceneKind::NewYearCountdown => "newyear-countdown",
+        });
+    }
+}
+
+/// Fall speeds of the flakes, in rows per update.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpeedRange {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl FromStr for SpeedRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (min, max) = s.split_once('-').unwrap_or((s, s));
+        let min: f32 = min.trim().parse().map_err(|_| "invalid minimum speed")?;
+        let max: f32 = max.trim().parse().map_err(|_| "invalid maximum speed")?;
+        if !(min > 0.0 && max >= min) {
+            return Err("speeds must be positive, the minimum first".to_owned());
+        }
+
+        return Ok(SpeedRange { min, max });
+    }
+}
+
+impl fmt::Display for SpeedRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.min == self.max {
+            return write!(f, "{}", self.min);
+        }
+        return write!(f, "{}-{}", self.min, self.max);
+    }
+}
+
+/// How the scene fills the terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    /// Everything at its natural size, spread over the whole terminal.
+    Auto,
+    /// The tree grown to fill the terminal.
+    Fullscreen,
+    /// A small scene centred in the terminal.
+    Compact,
+}
+
+impl FromStr for Layout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s {
+            "auto" => Ok(Layout::Auto),
+            "fullscreen" => Ok(Layout::Fullscreen),
+            "compact" => Ok(Layout::Compact),
+            _ => Err("expected auto, fullscreen or compact".to_owned()),
+        };
+    }
+}
+
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return f.write_str(match self {
+            Layout::Auto => "auto",
+            Layout::Fullscreen => "fullscreen",
+            Layout::Compact => "compact",
+        });
+    }
+}
+
+/// A character a single column wide.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FillChar(pub char);
+
+impl FromStr for FillChar {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        return match (chars.next(), chars.next()) {
+            (Some(c), None) if char_width(c) == 1 && !c.is_control() => Ok(FillChar(c)),
+            _ => Err("expected a single character one column wide".to_owned()),
+        };
+    }
+}
+
+/// A color given by name or `#rrggbb` code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FillColor(pub Color);
+
+impl FromStr for FillColor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return color::parse(s)
+            .map(FillColor)
+            .ok_or_else(|| "expected a color name or #rrggbb".to_owned());
+    }
+}
+
+impl fmt::Display for FillColor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return f.write_str(&color::name(self.0));
+    }
+}
+
+/// How each frame is put on the terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenderMode {
+    /// Clearing the screen with the `clear` command before drawing it all.
+    Clear,
+    /// Drawing it all over the last frame from the top left corner.
+    Overwrite,
+    /// Drawing only the cells that changed since the last frame.
+    Diff,
+}
+
+impl FromStr for RenderMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s {
+            "clear" => Ok(RenderMode::Clear),
+            "overwrite" => Ok(RenderMode::Overwrite),
+            "diff" => Ok(RenderMode::Diff),
+            _ => Err("expected clear, overwrite or diff".to_owned()),
+        };
+    }
+}
+
+impl fmt::Display for RenderMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return f.write_str(match self {
+            RenderMode::Clear => "clear",
+            RenderMode::Overwrite => "overwrite",
+            RenderMode::Diff => "diff",
+        });
+    }
+}
+
+/// File format of exported frames.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Ppm,
+}
+
+impl FromStr for ImageFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s {
+            "png" => Ok(ImageFormat::Png),
+            "ppm" => Ok(ImageFormat::Ppm),
+            _ => Err("expected png or ppm".to_owned()),
+        };
+    }
+}
+
+/// Which way the camera pans across the world.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PanDirection {
+    Left,
+    Right,
+}
+
+impl PanDirection {
+    /// Columns of the world per column panned, negative moving left.
+    pub fn sign(self) -> f32 {
+        return match self {
+            PanDirection::Left => -1.0,
+            PanDirection::Right => 1.0,
+        };
+    }
+}
+
+impl FromStr for PanDirection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s {
+            "left" => Ok(PanDirection::Left),
+            "right" => Ok(PanDirection::Right),
+            _ => Err("expected left or right".to_owned()),
+        };
+    }
+}
+
+impl fmt::Display for PanDirection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return f.write_str(match self {
+            PanDirection::Left => "left",
+            PanDirection::Right => "right",
+        });
+    }
+}
+
+/// A rectangle of the terminal, in columns and rows from the top left corner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Region {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl FromStr for Region {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let numbers = s
+            .split(',')
+            .map(|number| number.trim().parse::<usize>())
+            .collect::<Result<Vec<usize>, _>>()
+            .map_err(|error| error.to_string())?;
+
+        return match numbers[..] {
+            [_, _, 0, _] | [_, _, _, 0] => Err("the region must not be empty".to_owned()),
+            [x, y, width, height] => Ok(Region {
+                x,
+                y,
+                width,
+                height,
+            }),
+            _ => Err("expected four numbers".to_owned()),
+        };
+    }
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f, "{},{},{},{}", self.x, self.y, self.width, self.height);
+    }
+}
+
+/// A way of cutting back on the work of drawing a frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Downgrade {
+    /// Hides every layer by this name, one of `LAYERS`.
+    Layer(String),
+    /// Turns off the vignette.
+    Vignette,
+    /// Halves the snowfall, again each time it is taken.
+    Snowfall,
+}
+
+impl FromStr for Downgrade {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s {
+            "vignette" => Ok(Downgrade::Vignette),
+            "snowfall" => Ok(Downgrade::Snowfall),
+            layer if LAYERS.contains(&layer) => Ok(Downgrade::Layer(layer.to_owned())),
+            _ => Err(format!(
+                "unknown step '{}', expected vignette, snowfall or one of {}",
+                s,
+                LAYERS.join(", ")
+            )),
+        };
+    }
+}
+
+impl fmt::Display for Downgrade {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return f.write_str(match self {
+            Downgrade::Layer(layer) => layer,
+            Downgrade::Vignette => "vignette",
+            Downgrade::Snowfall => "snowfall",
+        });
+    }
+}
+
+/// Downgrades to take one after the other while frames run over budget.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DowngradeOrder(pub Vec<Downgrade>);
+
+impl FromStr for DowngradeOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Ok(DowngradeOrder(Vec::new()));
+        }
+        return s
+            .split(',')
+            .map(|step| step.trim().parse())
+            .collect::<Result<Vec<Downgrade>, String>>()
+            .map(DowngradeOrder);
+    }
+}
+
+impl fmt::Display for DowngradeOrder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let steps = self
+            .0
+            .iter()
+            .map(Downgrade::to_string)
+            .collect::<Vec<String>>();
+        return f.write_str(&steps.join(","));
+    }
+}
+
+pub enum Parsed {
+    Run(Box<Options>),
+    Help,
+}
+
+impl Options {
+    /// Parallax factor of a layer, if it scrolls.
+    pub fn parallax(&self, layer: &str) -> Option<f32> {
+        return self
+            .parallax
+            .iter()
+            .find(|parallax| parallax.layer == layer)
+            .map(|parallax| parallax.factor);
+    }
+
+    /// Sets the parallax of a layer, replacing any earlier one.
+    pub fn set_parallax(&mut self, parallax: LayerParallax) {
+        self.parallax.retain(|other| other.layer != parallax.layer);
+        self.parallax.push(parallax);
+    }
+
+    /// Seed of every frame, if seeded, from the number or else the text.
+    pub fn seed(&self) -> Option<u64> {
+        return self
+            .seed
+            .or_else(|| self.seed_text.as_deref().map(hash_seed));
+    }
+
+    /// File remembering the day of the last run for `--first-snow`, if
+    /// there is anywhere to keep it.
+    pub fn first_snow_state(&self) -> Option<PathBuf> {
+        if let Some(path) = &self.first_snow_state {
+            return Some(PathBuf::from(path));
+        }
+        let state_home = env::var_os("XDG_STATE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".local/state")))?;
+        return Some(state_home.join("cyber-christmas-card").join("last-run"));
+    }
+
+    /// Whether the tree is seeded by the date, no explicit seed winning over it.
+    pub fn tree_seeded_by_date(&self) -> bool {
+        return self.date_seed && self.tree_seed.or(self.seed()).is_none();
+    }
+
+    /// Seed of the tree, if seeded. An explicit seed wins over the date.
+    pub fn tree_seed(&self) -> Option<u64> {
+        return self.tree_seed.or(self.seed()).or_else(|| {
+            if !self.date_seed {
+                return None;
+            }
+            let today = Date::today();
+            return Some(today.year as u64 * 10_000 + today.month as u64 * 100 + today.day as u64);
+        });
+    }
+
+    /// Seed of the candles, if seeded.
+    pub fn candles_seed(&self) -> Option<u64> {
+        return self.seed().map(|seed| seed.wrapping_add(2));
+    }
+
+    /// Seed of the colors of the gifts, if seeded.
+    pub fn gifts_seed(&self) -> Option<u64> {
+        return self.seed().map(|seed| seed.wrapping_add(5));
+    }
+
+    /// Seed of the clouds, if seeded.
+    pub fn clouds_seed(&self) -> Option<u64> {
+        return self.seed().map(|seed| seed.wrapping_add(6));
+    }
+
+    /// Seed of the fireworks, if seeded.
+    pub fn fireworks_seed(&self) -> Option<u64> {
+        return self.seed().map(|seed| seed.wrapping_add(3));
+    }
+
+    /// Seed of the snow, if seeded. Offset from the global seed so the snow
+    /// does not draw the same numbers as the tree.
+    pub fn snow_seed(&self) -> Option<u64> {
+        return self
+            .snow_seed
+            .or_else(|| self.seed().map(|seed| seed.wrapping_add(1)));
+    }
+
+    /// Seed of the near layer of --layered-snow, if seeded.
+    pub fn near_snow_seed(&self) -> Option<u64> {
+        return self.snow_seed().map(|seed| seed.wrapping_add(4));
+    }
+
+    /// Seed of the `depth`th of the --snow-layers behind the first, if seeded.
+    pub fn far_snow_seed(&self, depth: usize) -> Option<u64> {
+        return self
+            .snow_seed()
+            .map(|seed| seed.wrapping_add(10 + depth as u64));
+    }
+
+    pub fn parse(args: impl Iterator<Item = String>) -> Result<Parsed, String> {
+        let args = args.collect::<Vec<String>>();
+        let mut options = Options::default();
+        if let Some(path) = config_path(&args) {
+            config::load(&path, &mut options)?;
+        }
+
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            // accept both `--flag value` and `--flag=value`
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => {
+                    (flag.to_owned(), Some(value.to_owned()))
+                }
+                _ => (arg, None),
+            };
+            let mut value = || inline_value.clone().or_else(|| args.next());
+
+            match flag.as_str() {
+                "--config" => {
+                    value();
+                }
+                "--ornament-density" => {
+                    options.ornament_density = parse_value(&flag, value())?;
+                }
+                "--tree-shape" => options.tree_shape = parse_value(&flag, value())?,
+                "--scene" => options.scene = Some(parse_value(&flag, value())?),
+                "--lang" => options.lang = parse_value(&flag, value())?,
+                "--year" => options.year = Some(parse_value(&flag, value())?),
+                "--wind" => options.wind = parse_value(&flag, value())?,
+                "--snow-speed" => options.snow_speed = parse_value(&flag, value())?,
+                "--snow-layers" => options.snow_layers = parse_value(&flag, value())?,
+                "--layered-snow" => options.layered_snow = true,
+                "--sparkle" => options.sparkle = true,
+                "--snow-prefill" => options.snow_prefill = true,
+                "--no-snow-prefill" => options.snow_prefill = false,
+                "--ground-snow" => options.ground_snow = true,
+                "--drift" => options.drift = true,
+                "--reflections" => options.reflections = true,
+                "--snow-caps" => options.snow_caps = true,
+                "--smooth-tree" => options.smooth_tree = true,
+                "--lights-charge" => options.lights_charge = true,
+                "--charge-seconds" => {
+                    options.lights_charge = true;
+                    options.lights_charge_seconds = parse_value(&flag, value())?;
+                }
+                "--candles" => options.candles = true,
+                "--aurora" => options.aurora = true,
+                "--aurora-wind" => {
+                    options.aurora = true;
+                    options.aurora_wind = true;
+                }
+                "--gifts" => options.gifts = true,
+                "--clouds" => options.clouds = true,
+                "--snow-from-clouds" => {
+                    options.clouds = true;
+                    options.snow_from_clouds = true;
+                }
+                "--window" => options.window = true,
+                "--window-ledge" => {
+                    options.window = true;
+                    options.window_ledge = parse_value(&flag, value())?;
+                }
+                "--snowman" => options.snowman = true,
+                "--snowman-melt" => {
+                    options.snowman = true;
+                    options.snowman_melt = Some(parse_value(&flag, value())?);
+                }
+                "--snowman-reset" => options.snowman_reset = true,
+                "--vignette" => options.vignette = parse_value(&flag, value())?,
+                "--sleigh" => options.sleigh = true,
+                "--sleigh-interval" => options.sleigh_interval = parse_value(&flag, value())?,
+                "--no-sleigh-bubble" => options.sleigh_bubble = false,
+                "--scroll-speed" => options.scroll_speed = parse_value(&flag, value())?,
+                "--parallax" => options.set_parallax(parse_value(&flag, value())?),
+                "--pan" => options.pan = parse_value(&flag, value())?,
+                "--pan-direction" => options.pan_direction = parse_value(&flag, value())?,
+                "--world-width" => options.world_width = Some(parse_value(&flag, value())?),
+                "--garland" => options.garland = true,
+                "--add-frame" => options.extra_frames.push(parse_value(&flag, value())?),
+                "--seed" => options.seed = Some(parse_value(&flag, value())?),
+                "--seed-text" => options.seed_text = Some(parse_value(&flag, value())?),
+                "--tree-seed" => options.tree_seed = Some(parse_value(&flag, value())?),
+                "--date-seed" => options.date_seed = true,
+                "--snow-seed" => options.snow_seed = Some(parse_value(&flag, value())?),
+                "--size" => options.size = Some(parse_value(&flag, value())?),
+                "--fullscreen" => options.layout = Layout::Fullscreen,
+                "--compact" => options.layout = Layout::Compact,
+                "--max-width" => options.max_width = Some(parse_value(&flag, value())?),
+                "--region" => options.region = Some(parse_value(&flag, value())?),
+                "--fill-char" => options.fill_char = parse_value(&flag, value())?,
+                "--fill-color" => options.fill_color = Some(parse_value(&flag, value())?),
+                "--title" => options.title = parse_value(&flag, value())?,
+                "--frames-dir" => options.frames_dir = Some(parse_value(&flag, value())?),
+                "--frames-format" => options.frames_format = parse_value(&flag, value())?,
+                "--render-mode" => options.render_mode = parse_value(&flag, value())?,
+                "--precompute" => options.precompute = Some(parse_value(&flag, value())?),
+                "--replay" => options.replay = Some(parse_value(&flag, value())?),
+                "--bpm" => options.bpm = Some(parse_value(&flag, value())?),
+                "--message" => options.messages.push(parse_value(&flag, value())?),
+                "--message-interval" => {
+                    options.message_interval = parse_value(&flag, value())?;
+                }
+                "--message-fade" => options.message_fade = true,
+                "--no-blessing" => options.blessing = false,
+                "--blessing-align" => options.blessing_align = parse_value(&flag, value())?,
+                "--blessing-offset" => options.blessing_offset = parse_value(&flag, value())?,
+                "--typewriter" => options.typewriter = true,
+                "--to" => options.to = Some(parse_value(&flag, value())?),
+                "--from" => options.from = Some(parse_value(&flag, value())?),
+                "--first-snow" => options.first_snow = true,
+                "--first-snow-state" => {
+                    options.first_snow = true;
+                    options.first_snow_state = Some(parse_value(&flag, value())?);
+                }
+                "--interval" => options.interval = parse_value(&flag, value())?,
+                "--max-frame-ms" => options.max_frame_ms = Some(parse_value(&flag, value())?),
+                "--downgrade" => options.downgrade = parse_value(&flag, value())?,
+                "--frames" => options.frames = Some(parse_value(&flag, value())?),
+                "--no-fade" => options.fade = false,
+                "--autoscale" => options.autoscale = true,
+                "--kiosk" => options.kiosk = true,
+                "--dry-run" => options.dry_run = true,
+                "--save-config" => options.save_config = Some(parse_value(&flag, value())?),
+                "--focus-reactive" => options.focus_reactive = true,
+                "--control-stdin" => options.control_stdin = true,
+                "--reverse-layers" => options.reverse_layers = true,
+                "--debug" => options.debug = true,
+                "--debug-overlap" => options.debug_overlap = true,
+                "--no-catch-panics" => options.catch_panics = false,
+                "-h" | "--help" => return Ok(Parsed::Help),
+                _ => return Err(format!("unexpected argument '{}'\n\n{}", flag, USAGE)),
+            }
+        }
+
+        let ranged = [
+            (
+                "--scroll-speed",
+                Some(options.scroll_speed),
+                SCROLL_SPEED_RANGE,
+            ),
+            ("--bpm", options.bpm, BPM_RANGE),
+            (
+                "--message-interval",
+                Some(options.message_interval),
+                MESSAGE_INTERVAL_RANGE,
+            ),
+            ("--pan", Some(options.pan), PAN_RANGE),
+            (
+                "--charge-seconds",
+                Some(options.lights_charge_seconds),
+                CHARGE_SECONDS_RANGE,
+            ),
+            ("--snowman-melt", options.snowman_melt, SNOWMAN_MELT_RANGE),
+            ("--vignette", Some(options.vignette), VIGNETTE_RANGE),
+        ]
+        .into_iter()
+        .chain(
+            options
+                .parallax
+                .iter()
+                .map(|parallax| ("--parallax", Some(parallax.factor), PARALLAX_RANGE)),
+        );
+        for (flag, value, range) in ranged {
+            if let Some(value) = value {
+                check_range(flag, value as f64, range)?;
+            }
+        }
+        check_range(
+            "--ornament-density",
+            options.ornament_density,
+            ORNAMENT_DENSITY_RANGE,
+        )?;
+        if options.max_frame_ms == Some(0) {
+            return Err("the frame budget must be at least a millisecond".to_owned());
+        }
+        if options.snow_layers == 0 {
+            return Err("there must be at least one snow layer".to_owned());
+        }
+        if options.frames_dir.is_some() {
+            if cfg!(not(feature = "image")) {
+                return Err("--frames-dir needs a build with the image feature".to_owned());
+            }
+            if options.frames.is_none() {
+                return Err("--frames-dir needs --frames to know when to stop".to_owned());
+            }
+        }
+        if let Some(name) = options
+            .extra_frames
+            .iter()
+            .find(|name| !frame_names().contains(name))
+        {
+            return Err(format!(
+                "unknown frame '{}', expected one of {}",
+                name,
+                frame_names().join(", ")
+            ));
+        }
+        if options.max_width == Some(0) {
+            return Err("the max width must not be zero".to_owned());
+        }
+        if blessing_template(&options.lang).is_none() {
+            return Err(format!(
+                "unknown language '{}', expected one of {}",
+                options.lang,
+                languages()
+            ));
+        }
+
+        return Ok(Parsed::Run(Box::new(options)));
+    }
+}
+
+fn check_range(flag: &str, value: f64, range: RangeInclusive<f64>) -> Result<(), String> {
+    if range.contains(&value) {
+        return Ok(());
+    }
+
+    return Err(format!(
+        "{} must be between {:.1} and {:.1}, got {}",
+        flag,
+        range.start(),
+        range.end(),
+        value
+    ));
+}
+
+/// Hashes `text` into a seed with 64-bit FNV-1a, which unlike the standard
+/// library's hasher stays the same from one build to the next, so a shared
+/// seed text keeps giving the same card.
+fn hash_seed(text: &str) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in text.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+
+    return hash;
+}
+
+/// Codes of the bundled languages, comma separated.
+pub fn languages() -> String {
+    return BLESSINGS
+        .iter()
+        .map(|(code, _)| *code)
+        .collect::<Vec<&str>>()
+        .join(", ");
+}
+
+fn config_path(args: &[String]) -> Option<String> {
+    for (i, arg) in args.iter().enumerate() {
+        if arg == "--config" {
+            return args.get(i + 1).cloned();
+        } else if let Some(path) = arg.strip_prefix("--config=") {
+            return Some(path.to_owned());
+        }
+    }
+
+    return None;
+}
+
+fn parse_value<T: FromStr>(flag: &str, value: Option<String>) -> Result<T, String>
+where
+    T::Err: fmt::Display,
+{
+    let value = value.ok_or_else(|| format!("a value is required for '{}'", flag))?;
+    return value
+        .parse()
+        .map_err(|error| format!("invalid value '{}' for '{}': {}", value, flag, error));
+}
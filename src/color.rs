@@ -0,0 +1,227 @@
+use colored::Color;
+
+/// Approximate RGB value of a color, using the xterm palette for named colors.
+pub fn to_rgb(color: Color) -> (u8, u8, u8) {
+    return match color {
+        Color::Black => (0, 0, 0),
+        Color::Red => (205, 0, 0),
+        Color::Green => (0, 205, 0),
+        Color::Yellow => (205, 205, 0),
+        Color::Blue => (0, 0, 238),
+        Color::Magenta => (205, 0, 205),
+        Color::Cyan => (0, 205, 205),
+        Color::White => (229, 229, 229),
+        Color::BrightBlack => (127, 127, 127),
+        Color::BrightRed => (255, 0, 0),
+        Color::BrightGreen => (0, 255, 0),
+        Color::BrightYellow => (255, 255, 0),
+        Color::BrightBlue => (92, 92, 255),
+        Color::BrightMagenta => (255, 0, 255),
+        Color::BrightCyan => (0, 255, 255),
+        Color::BrightWhite => (255, 255, 255),
+        Color::TrueColor { r, g, b } => (r, g, b),
+    };
+}
+
+/// Multiplies the brightness of a color by `factor`, `0.0` giving black.
+pub fn scale(color: Color, factor: f32) -> Color {
+    let (r, g, b) = to_rgb(color);
+    let factor = factor.clamp(0.0, 1.0);
+    return Color::TrueColor {
+        r: (r as f32 * factor).round() as u8,
+        g: (g as f32 * factor).round() as u8,
+        b: (b as f32 * factor).round() as u8,
+    };
+}
+
+/// Blends `from` towards `to`, `0.0` giving `from` and `1.0` giving `to`.
+pub fn mix(from: Color, to: Color, amount: f32) -> Color {
+    let (from_r, from_g, from_b) = to_rgb(from);
+    let (to_r, to_g, to_b) = to_rgb(to);
+    let amount = amount.clamp(0.0, 1.0);
+    let channel =
+        |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * amount).round() as u8;
+    return Color::TrueColor {
+        r: channel(from_r, to_r),
+        g: channel(from_g, to_g),
+        b: channel(from_b, to_b),
+    };
+}
+
+/// Relative luminance of a color as perceived, from `0.0` for black to `1.0`
+/// for white.
+pub fn luminance(color: Color) -> f32 {
+    // https://www.w3.org/TR/WCAG21/#dfn-relative-luminance
+    let linear = |channel: u8| {
+        let channel = channel as f32 / 255.0;
+        if channel <= 0.040_45 {
+            return channel / 12.92;
+        }
+        return ((channel + 0.055) / 1.055).powf(2.4);
+    };
+    let (r, g, b) = to_rgb(color);
+    return 0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b);
+}
+
+/// How well `a` and `b` stand out against each other, from `1.0` for the
+/// same luminance to `21.0` for black on white.
+pub fn contrast(a: Color, b: Color) -> f32 {
+    let (lighter, darker) = (
+        luminance(a).max(luminance(b)),
+        luminance(a).min(luminance(b)),
+    );
+    return (lighter + 0.05) / (darker + 0.05);
+}
+
+/// Whichever of `choices` stands out the most against `background`, e.g. a
+/// ribbon for a box of some color, or `background` itself if there are no
+/// choices.
+pub fn contrasting(background: Color, choices: &[Color]) -> Color {
+    return choices
+        .iter()
+        .copied()
+        .max_by(|a, b| contrast(background, *a).total_cmp(&contrast(background, *b)))
+        .unwrap_or(background);
+}
+
+// the 16 named colors in the order of their palette indices
+const NAMED: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+    Color::BrightBlack,
+    Color::BrightRed,
+    Color::BrightGreen,
+    Color::BrightYellow,
+    Color::BrightBlue,
+    Color::BrightMagenta,
+    Color::BrightCyan,
+    Color::BrightWhite,
+];
+// channel levels of the 6 x 6 x 6 color cube of the 256 color palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let channel = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    return channel(a.0, b.0) + channel(a.1, b.1) + channel(a.2, b.2);
+}
+
+/// Index of the closest color of the xterm 256 color palette, for terminals
+/// without true color. Named colors keep their own index below 16, while
+/// other colors go to the closest of the color cube and the grey ramp.
+pub fn to_ansi256(color: Color) -> u8 {
+    if let Some(index) = NAMED.iter().position(|named| *named == color) {
+        return index as u8;
+    }
+
+    let rgb = to_rgb(color);
+    let level = |channel: u8| {
+        return (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - channel as i32).abs())
+            .unwrap();
+    };
+    let (r, g, b) = (level(rgb.0), level(rgb.1), level(rgb.2));
+    let cube = (CUBE_LEVELS[r], CUBE_LEVELS[g], CUBE_LEVELS[b]);
+    // 24 greys from 8 to 238 in steps of 10
+    let average = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+    let grey = (average.saturating_sub(3) / 10).min(23) as u8;
+    let grey_level = 8 + 10 * grey;
+
+    if distance(rgb, (grey_level, grey_level, grey_level)) < distance(rgb, cube) {
+        return 232 + grey;
+    }
+    return (16 + 36 * r + 6 * g + b) as u8;
+}
+
+/// The closest of the 16 named colors, for terminals with no more than those.
+pub fn to_ansi16(color: Color) -> Color {
+    let rgb = to_rgb(color);
+    return NAMED
+        .iter()
+        .copied()
+        .min_by_key(|named| distance(rgb, to_rgb(*named)))
+        .unwrap();
+}
+
+/// Names `color` the way `parse` reads it back, e.g. `bright blue` or `#1e90ff`.
+pub fn name(color: Color) -> String {
+    let name = match color {
+        Color::Black => "black",
+        Color::Red => "red",
+        Color::Green => "green",
+        Color::Yellow => "yellow",
+        Color::Blue => "blue",
+        Color::Magenta => "magenta",
+        Color::Cyan => "cyan",
+        Color::White => "white",
+        Color::BrightBlack => "bright black",
+        Color::BrightRed => "bright red",
+        Color::BrightGreen => "bright green",
+        Color::BrightYellow => "bright yellow",
+        Color::BrightBlue => "bright blue",
+        Color::BrightMagenta => "bright magenta",
+        Color::BrightCyan => "bright cyan",
+        Color::BrightWhite => "bright white",
+        Color::TrueColor { r, g, b } => return format!("#{:02x}{:02x}{:02x}", r, g, b),
+    };
+    return name.to_owned();
+}
+
+/// Parses a color name such as `blue` or `bright blue`, or a `#rrggbb` hex code.
+pub fn parse(s: &str) -> Option<Color> {
+    if let Some(hex) = s.strip_prefix('#') {
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        return Some(Color::TrueColor {
+            r: channel(0)?,
+            g: channel(2)?,
+            b: channel(4)?,
+        });
+    }
+
+    return s.parse().ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb(r: u8, g: u8, b: u8) -> Color {
+        return Color::TrueColor { r, g, b };
+    }
+
+    #[test]
+    fn downgrades_to_the_closest_palette_colors() {
+        // the brown of the trunk
+        let brown = rgb(139, 69, 19);
+        assert_eq!(to_ansi256(brown), 94);
+        assert_eq!(to_ansi16(brown), Color::Red);
+
+        assert_eq!(to_ansi256(rgb(0, 0, 0)), 16);
+        assert_eq!(to_ansi256(rgb(255, 255, 255)), 231);
+        assert_eq!(to_ansi16(rgb(0, 0, 0)), Color::Black);
+        assert_eq!(to_ansi16(rgb(255, 255, 255)), Color::BrightWhite);
+
+        assert_eq!(to_ansi256(rgb(255, 0, 0)), 196);
+        assert_eq!(to_ansi256(rgb(0, 255, 0)), 46);
+        assert_eq!(to_ansi256(rgb(0, 0, 255)), 21);
+        assert_eq!(to_ansi16(rgb(255, 0, 0)), Color::BrightRed);
+        assert_eq!(to_ansi16(rgb(0, 255, 0)), Color::BrightGreen);
+        assert_eq!(to_ansi16(rgb(0, 0, 255)), Color::Blue);
+
+        // greys between the levels of the cube go to the grey ramp
+        assert_eq!(to_ansi256(rgb(118, 118, 118)), 243);
+        assert_eq!(to_ansi16(rgb(118, 118, 118)), Color::BrightBlack);
+
+        // named colors are already in both palettes
+        assert_eq!(to_ansi256(Color::BrightCyan), 14);
+        assert_eq!(to_ansi16(Color::Magenta), Color::Magenta);
+    }
+}
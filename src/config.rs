@@ -0,0 +1,1061 @@
+use std::{
+    fmt::{Debug, Display},
+    fs,
+    ops::RangeInclusive,
+    str::FromStr,
+};
+
+use toml::{Table, Value};
+
+use crate::cli::{self, Options};
+use cyber_christmas_card::{blessing_template, frame_names};
+
+/// Loads the TOML config at `path` on top of `options`.
+///
+/// ```toml
+/// year = 2025
+/// messages = ["Merry Christmas {year}", "Happy holidays"]
+/// wind = 1
+/// seed = 42
+///
+/// [tree]
+/// ornament-density = 0.2
+/// shape = "tiered"
+/// snow-caps = true
+/// smooth = true
+///
+/// [camera]
+/// pan = 1
+/// world-width = 240
+///
+/// [garland]
+/// enabled = true
+///
+/// [snow]
+/// ground = true
+///
+/// [sleigh]
+/// enabled = true
+/// parallax = 0.5
+/// interval = 30
+/// ```
+///
+/// Every offending field is reported, one per line, rather than only the first.
+pub fn load(path: &str, options: &mut Options) -> Result<(), String> {
+    let text = fs::read_to_string(path)
+        .map_err(|error| format!("cannot read config '{}': {}", path, error))?;
+    return apply(&text, options).map_err(|error| format!("invalid config '{}':\n{}", path, error));
+}
+
+pub fn apply(text: &str, options: &mut Options) -> Result<(), String> {
+    let table = text
+        .parse::<Table>()
+        .map_err(|error| error.to_string().trim_end().to_owned())?;
+
+    let mut validator = Validator::default();
+    let root = Section {
+        table: &table,
+        path: String::new(),
+    };
+    validator.check_keys(
+        &root,
+        &[
+            "scene",
+            "lang",
+            "year",
+            "wind",
+            "scroll-speed",
+            "seed",
+            "seed-text",
+            "size",
+            "region",
+            "max-width",
+            "layout",
+            "autoscale",
+            "title",
+            "fill-char",
+            "fill-color",
+            "interval",
+            "max-frame-ms",
+            "downgrade",
+            "render-mode",
+            "bpm",
+            "messages",
+            "message-interval",
+            "message-fade",
+            "blessing",
+            "blessing-align",
+            "blessing-offset",
+            "typewriter",
+            "to",
+            "from",
+            "first-snow",
+            "first-snow-state",
+            "frames",
+            "fade",
+            "kiosk",
+            "reverse-layers",
+            "focus-reactive",
+            "control-stdin",
+            "add-frames",
+            "camera",
+            "tree",
+            "garland",
+            "snow",
+            "sleigh",
+            "candles",
+            "snowman",
+            "gifts",
+            "aurora",
+            "clouds",
+            "window",
+        ],
+    );
+    if let Some(scene) = validator.parsed(&root, "scene", "\"christmas\" or \"newyear-countdown\"")
+    {
+        options.scene = Some(scene);
+    }
+    if let Some(lang) = validator.string(&root, "lang") {
+        if blessing_template(&lang).is_some() {
+            options.lang = lang;
+        } else {
+            validator.errors.push(format!(
+                "`lang` should be one of {}, found {:?}",
+                cli::languages(),
+                lang
+            ));
+        }
+    }
+    if let Some(year) = validator.integer(&root, "year") {
+        options.year = Some(year);
+    }
+    if let Some(wind) = validator.integer(&root, "wind") {
+        options.wind = wind;
+    }
+    if let Some(scroll_speed) = validator.float(&root, "scroll-speed", cli::SCROLL_SPEED_RANGE) {
+        options.scroll_speed = scroll_speed as f32;
+    }
+    if let Some(seed) = validator.integer(&root, "seed") {
+        options.seed = Some(seed);
+    }
+    if let Some(seed_text) = validator.string(&root, "seed-text") {
+        options.seed_text = Some(seed_text);
+    }
+    if let Some(size) = validator.parsed(&root, "size", "a size like \"80x24\"") {
+        options.size = Some(size);
+    }
+    if let Some(region) = validator.parsed(&root, "region", "a region like \"0,0,40,20\"") {
+        options.region = Some(region);
+    }
+    if let Some(max_width) = validator.integer(&root, "max-width") {
+        options.max_width = Some(max_width);
+    }
+    if let Some(layout) =
+        validator.parsed(&root, "layout", "\"auto\", \"fullscreen\" or \"compact\"")
+    {
+        options.layout = layout;
+    }
+    if let Some(autoscale) = validator.boolean(&root, "autoscale") {
+        options.autoscale = autoscale;
+    }
+    if let Some(fill_char) = validator.parsed(&root, "fill-char", "a single character") {
+        options.fill_char = fill_char;
+    }
+    if let Some(fill_color) = validator.parsed(&root, "fill-color", "a color") {
+        options.fill_color = Some(fill_color);
+    }
+    if let Some(title) = validator.string(&root, "title") {
+        options.title = title;
+    }
+    if let Some(interval) = validator.integer(&root, "interval") {
+        options.interval = interval;
+    }
+    if let Some(max_frame_ms) = validator.integer(&root, "max-frame-ms") {
+        options.max_frame_ms = Some(max_frame_ms);
+    }
+    if let Some(downgrade) = validator.parsed(
+        &root,
+        "downgrade",
+        "comma separated layers, \"vignette\" or \"snowfall\"",
+    ) {
+        options.downgrade = downgrade;
+    }
+    if let Some(render_mode) =
+        validator.parsed(&root, "render-mode", "\"clear\", \"overwrite\" or \"diff\"")
+    {
+        options.render_mode = render_mode;
+    }
+    if let Some(bpm) = validator.float(&root, "bpm", cli::BPM_RANGE) {
+        options.bpm = Some(bpm as f32);
+    }
+    if let Some(messages) = validator.strings(&root, "messages") {
+        options.messages = messages;
+    }
+    if let Some(interval) = validator.float(&root, "message-interval", cli::MESSAGE_INTERVAL_RANGE)
+    {
+        options.message_interval = interval as f32;
+    }
+    if let Some(fade) = validator.boolean(&root, "message-fade") {
+        options.message_fade = fade;
+    }
+    if let Some(blessing) = validator.boolean(&root, "blessing") {
+        options.blessing = blessing;
+    }
+    if let Some(align) =
+        validator.parsed(&root, "blessing-align", "\"left\", \"center\" or \"right\"")
+    {
+        options.blessing_align = align;
+    }
+    if let Some(offset) = validator.integer(&root, "blessing-offset") {
+        options.blessing_offset = offset;
+    }
+    if let Some(typewriter) = validator.boolean(&root, "typewriter") {
+        options.typewriter = typewriter;
+    }
+    if let Some(to) = validator.string(&root, "to") {
+        options.to = Some(to);
+    }
+    if let Some(from) = validator.string(&root, "from") {
+        options.from = Some(from);
+    }
+    if let Some(first_snow) = validator.boolean(&root, "first-snow") {
+        options.first_snow = first_snow;
+    }
+    if let Some(path) = validator.string(&root, "first-snow-state") {
+        options.first_snow = true;
+        options.first_snow_state = Some(path);
+    }
+    if let Some(frames) = validator.integer(&root, "frames") {
+        options.frames = Some(frames);
+    }
+    if let Some(fade) = validator.boolean(&root, "fade") {
+        options.fade = fade;
+    }
+    if let Some(kiosk) = validator.boolean(&root, "kiosk") {
+        options.kiosk = kiosk;
+    }
+    if let Some(reverse_layers) = validator.boolean(&root, "reverse-layers") {
+        options.reverse_layers = reverse_layers;
+    }
+    if let Some(focus_reactive) = validator.boolean(&root, "focus-reactive") {
+        options.focus_reactive = focus_reactive;
+    }
+    if let Some(control_stdin) = validator.boolean(&root, "control-stdin") {
+        options.control_stdin = control_stdin;
+    }
+    if let Some(names) = validator.strings(&root, "add-frames") {
+        match names.iter().find(|name| !frame_names().contains(name)) {
+            Some(name) => validator.errors.push(format!(
+                "`add-frames` should hold frame names, one of {}, found {:?}",
+                frame_names().join(", "),
+                name
+            )),
+            None => options.extra_frames = names,
+        }
+    }
+
+    if let Some(camera) = validator.section(&root, "camera") {
+        validator.check_keys(&camera, &["pan", "direction", "world-width"]);
+        if let Some(pan) = validator.float(&camera, "pan", cli::PAN_RANGE) {
+            options.pan = pan as f32;
+        }
+        if let Some(direction) = validator.parsed(&camera, "direction", "\"left\" or \"right\"") {
+            options.pan_direction = direction;
+        }
+        if let Some(world_width) = validator.integer(&camera, "world-width") {
+            options.world_width = Some(world_width);
+        }
+    }
+
+    if let Some(tree) = validator.section(&root, "tree") {
+        validator.check_keys(
+            &tree,
+            &[
+                "ornament-density",
+                "shape",
+                "seed",
+                "date-seed",
+                "snow-caps",
+                "smooth",
+                "lights-charge",
+                "charge-seconds",
+                "parallax",
+            ],
+        );
+        parallax(&mut validator, &tree, "tree", options);
+        if let Some(density) =
+            validator.float(&tree, "ornament-density", cli::ORNAMENT_DENSITY_RANGE)
+        {
+            options.ornament_density = density;
+        }
+        if let Some(shape) =
+            validator.parsed(&tree, "shape", "\"triangle\", \"tiered\" or \"rounded\"")
+        {
+            options.tree_shape = shape;
+        }
+        if let Some(seed) = validator.integer(&tree, "seed") {
+            options.tree_seed = Some(seed);
+        }
+        if let Some(date_seed) = validator.boolean(&tree, "date-seed") {
+            options.date_seed = date_seed;
+        }
+        if let Some(snow_caps) = validator.boolean(&tree, "snow-caps") {
+            options.snow_caps = snow_caps;
+        }
+        if let Some(smooth) = validator.boolean(&tree, "smooth") {
+            options.smooth_tree = smooth;
+        }
+        if let Some(lights_charge) = validator.boolean(&tree, "lights-charge") {
+            options.lights_charge = lights_charge;
+        }
+        if let Some(seconds) = validator.float(&tree, "charge-seconds", cli::CHARGE_SECONDS_RANGE) {
+            options.lights_charge_seconds = seconds as f32;
+        }
+    }
+
+    if let Some(garland) = validator.section(&root, "garland") {
+        validator.check_keys(&garland, &["enabled", "parallax"]);
+        parallax(&mut validator, &garland, "garland", options);
+        if let Some(enabled) = validator.boolean(&garland, "enabled") {
+            options.garland = enabled;
+        }
+    }
+
+    if let Some(snow) = validator.section(&root, "snow") {
+        validator.check_keys(
+            &snow,
+            &[
+                "seed",
+                "speed",
+                "prefill",
+                "layers",
+                "layered",
+                "sparkle",
+                "from-clouds",
+                "ground",
+                "drift",
+                "reflections",
+                "parallax",
+            ],
+        );
+        parallax(&mut validator, &snow, "snow", options);
+        if let Some(seed) = validator.integer(&snow, "seed") {
+            options.snow_seed = Some(seed);
+        }
+        if let Some(speed) = validator.parsed(&snow, "speed", "a speed like \"1\" or \"0.5-2\"") {
+            options.snow_speed = speed;
+        }
+        if let Some(layers) = validator.integer(&snow, "layers") {
+            options.snow_layers = layers;
+        }
+        if let Some(layered) = validator.boolean(&snow, "layered") {
+            options.layered_snow = layered;
+        }
+        if let Some(sparkle) = validator.boolean(&snow, "sparkle") {
+            options.sparkle = sparkle;
+        }
+        if let Some(from_clouds) = validator.boolean(&snow, "from-clouds") {
+            options.snow_from_clouds = from_clouds;
+        }
+        if let Some(prefill) = validator.boolean(&snow, "prefill") {
+            options.snow_prefill = prefill;
+        }
+        if let Some(ground) = validator.boolean(&snow, "ground") {
+            options.ground_snow = ground;
+        }
+        if let Some(drift) = validator.boolean(&snow, "drift") {
+            options.drift = drift;
+        }
+        if let Some(reflections) = validator.boolean(&snow, "reflections") {
+            options.reflections = reflections;
+        }
+    }
+
+    if let Some(sleigh) = validator.section(&root, "sleigh") {
+        validator.check_keys(&sleigh, &["enabled", "interval", "bubble", "parallax"]);
+        parallax(&mut validator, &sleigh, "sleigh", options);
+        if let Some(enabled) = validator.boolean(&sleigh, "enabled") {
+            options.sleigh = enabled;
+        }
+        if let Some(interval) = validator.integer(&sleigh, "interval") {
+            options.sleigh_interval = interval;
+        }
+        if let Some(bubble) = validator.boolean(&sleigh, "bubble") {
+            options.sleigh_bubble = bubble;
+        }
+    }
+
+    if let Some(aurora) = validator.section(&root, "aurora") {
+        validator.check_keys(&aurora, &["enabled", "wind", "parallax"]);
+        parallax(&mut validator, &aurora, "aurora", options);
+        if let Some(enabled) = validator.boolean(&aurora, "enabled") {
+            options.aurora = enabled;
+        }
+        if let Some(wind) = validator.boolean(&aurora, "wind") {
+            options.aurora_wind = wind;
+        }
+    }
+
+    if let Some(gifts) = validator.section(&root, "gifts") {
+        validator.check_keys(&gifts, &["enabled", "parallax"]);
+        parallax(&mut validator, &gifts, "gifts", options);
+        if let Some(enabled) = validator.boolean(&gifts, "enabled") {
+            options.gifts = enabled;
+        }
+    }
+
+    if let Some(clouds) = validator.section(&root, "clouds") {
+        validator.check_keys(&clouds, &["enabled", "parallax"]);
+        parallax(&mut validator, &clouds, "clouds", options);
+        if let Some(enabled) = validator.boolean(&clouds, "enabled") {
+            options.clouds = enabled;
+        }
+    }
+
+    if let Some(window) = validator.section(&root, "window") {
+        validator.check_keys(&window, &["enabled", "ledge"]);
+        if let Some(enabled) = validator.boolean(&window, "enabled") {
+            options.window = enabled;
+        }
+        if let Some(ledge) = validator.integer(&window, "ledge") {
+            options.window_ledge = ledge;
+        }
+    }
+
+    if let Some(snowman) = validator.section(&root, "snowman") {
+        validator.check_keys(&snowman, &["enabled", "melt", "reset", "parallax"]);
+        parallax(&mut validator, &snowman, "snowman", options);
+        if let Some(enabled) = validator.boolean(&snowman, "enabled") {
+            options.snowman = enabled;
+        }
+        if let Some(melt) = validator.float(&snowman, "melt", cli::SNOWMAN_MELT_RANGE) {
+            options.snowman_melt = Some(melt as f32);
+        }
+        if let Some(reset) = validator.boolean(&snowman, "reset") {
+            options.snowman_reset = reset;
+        }
+    }
+
+    if let Some(candles) = validator.section(&root, "candles") {
+        validator.check_keys(&candles, &["enabled", "vignette", "parallax"]);
+        parallax(&mut validator, &candles, "candles", options);
+        if let Some(enabled) = validator.boolean(&candles, "enabled") {
+            options.candles = enabled;
+        }
+        if let Some(vignette) = validator.float(&candles, "vignette", cli::VIGNETTE_RANGE) {
+            options.vignette = vignette as f32;
+        }
+    }
+
+    if validator.errors.is_empty() {
+        return Ok(());
+    } else {
+        return Err(validator.errors.join("\n"));
+    }
+}
+
+/// Writes the options a config file can set to `path`, for `load` to bring
+/// back the same card later.
+pub fn save(path: &str, options: &Options) -> Result<(), String> {
+    let text = to_toml(options);
+    // such as a seed too large for a TOML integer
+    apply(&text, &mut Options::default())
+        .map_err(|error| format!("cannot save the options to a config file: {}", error))?;
+    return fs::write(path, text)
+        .map_err(|error| format!("cannot write config '{}': {}", path, error));
+}
+
+/// The options a config file can set, as TOML that `apply` reads back into
+/// the same options.
+pub fn to_toml(options: &Options) -> String {
+    let mut writer = Writer::default();
+    writer.display(
+        "scene",
+        options.scene.map(|scene| quote(&scene.to_string())),
+    );
+    writer.display("lang", Some(quote(&options.lang)));
+    writer.display("year", options.year);
+    writer.display("wind", Some(options.wind));
+    writer.display("scroll-speed", Some(float(options.scroll_speed)));
+    writer.display("seed", options.seed);
+    writer.display("seed-text", options.seed_text.as_deref().map(quote));
+    writer.display("size", options.size.map(|size| quote(&size.to_string())));
+    writer.display(
+        "region",
+        options.region.map(|region| quote(&region.to_string())),
+    );
+    writer.display("max-width", options.max_width);
+    writer.display("layout", Some(quote(&options.layout.to_string())));
+    writer.display("autoscale", Some(options.autoscale));
+    writer.display("title", Some(quote(&options.title)));
+    writer.display("fill-char", Some(quote(&options.fill_char.0.to_string())));
+    writer.display(
+        "fill-color",
+        options.fill_color.map(|color| quote(&color.to_string())),
+    );
+    writer.display("interval", Some(options.interval));
+    writer.display("max-frame-ms", options.max_frame_ms);
+    writer.display("downgrade", Some(quote(&options.downgrade.to_string())));
+    writer.display("render-mode", Some(quote(&options.render_mode.to_string())));
+    writer.display("bpm", options.bpm.map(float));
+    writer.display("messages", Some(strings(&options.messages)));
+    writer.display("message-interval", Some(float(options.message_interval)));
+    writer.display("message-fade", Some(options.message_fade));
+    writer.display("blessing", Some(options.blessing));
+    writer.display(
+        "blessing-align",
+        Some(quote(&options.blessing_align.to_string())),
+    );
+    writer.display("blessing-offset", Some(options.blessing_offset));
+    writer.display("typewriter", Some(options.typewriter));
+    writer.display("to", options.to.as_deref().map(quote));
+    writer.display("from", options.from.as_deref().map(quote));
+    writer.display("first-snow", Some(options.first_snow));
+    writer.display(
+        "first-snow-state",
+        options.first_snow_state.as_deref().map(quote),
+    );
+    writer.display("frames", options.frames);
+    writer.display("fade", Some(options.fade));
+    writer.display("kiosk", Some(options.kiosk));
+    writer.display("reverse-layers", Some(options.reverse_layers));
+    writer.display("focus-reactive", Some(options.focus_reactive));
+    writer.display("control-stdin", Some(options.control_stdin));
+    writer.display("add-frames", Some(strings(&options.extra_frames)));
+
+    writer.section("camera");
+    writer.display("pan", Some(float(options.pan)));
+    writer.display("direction", Some(quote(&options.pan_direction.to_string())));
+    writer.display("world-width", options.world_width);
+
+    writer.layer("tree", options);
+    writer.display("ornament-density", Some(float(options.ornament_density)));
+    writer.display("shape", Some(quote(&options.tree_shape.to_string())));
+    writer.display("seed", options.tree_seed);
+    writer.display("date-seed", Some(options.date_seed));
+    writer.display("snow-caps", Some(options.snow_caps));
+    writer.display("smooth", Some(options.smooth_tree));
+    writer.display("lights-charge", Some(options.lights_charge));
+    writer.display("charge-seconds", Some(float(options.lights_charge_seconds)));
+
+    writer.layer("garland", options);
+    writer.display("enabled", Some(options.garland));
+
+    writer.layer("snow", options);
+    writer.display("seed", options.snow_seed);
+    writer.display("speed", Some(quote(&options.snow_speed.to_string())));
+    writer.display("prefill", Some(options.snow_prefill));
+    writer.display("layers", Some(options.snow_layers));
+    writer.display("layered", Some(options.layered_snow));
+    writer.display("sparkle", Some(options.sparkle));
+    writer.display("from-clouds", Some(options.snow_from_clouds));
+    writer.display("ground", Some(options.ground_snow));
+    writer.display("drift", Some(options.drift));
+    writer.display("reflections", Some(options.reflections));
+
+    writer.layer("sleigh", options);
+    writer.display("enabled", Some(options.sleigh));
+    writer.display("interval", Some(options.sleigh_interval));
+    writer.display("bubble", Some(options.sleigh_bubble));
+
+    writer.layer("aurora", options);
+    writer.display("enabled", Some(options.aurora));
+    writer.display("wind", Some(options.aurora_wind));
+
+    writer.layer("gifts", options);
+    writer.display("enabled", Some(options.gifts));
+
+    writer.layer("clouds", options);
+    writer.display("enabled", Some(options.clouds));
+
+    writer.section("window");
+    writer.display("enabled", Some(options.window));
+    writer.display("ledge", Some(options.window_ledge));
+
+    writer.layer("snowman", options);
+    writer.display("enabled", Some(options.snowman));
+    writer.display("melt", options.snowman_melt.map(float));
+    writer.display("reset", Some(options.snowman_reset));
+
+    writer.layer("candles", options);
+    writer.display("enabled", Some(options.candles));
+    writer.display("vignette", Some(float(options.vignette)));
+
+    return writer.text;
+}
+
+#[derive(Default)]
+struct Writer {
+    text: String,
+}
+
+impl Writer {
+    fn display(&mut self, key: &str, value: Option<impl Display>) {
+        if let Some(value) = value {
+            self.text.push_str(&format!("{} = {}\n", key, value));
+        }
+    }
+
+    fn section(&mut self, name: &str) {
+        self.text.push_str(&format!("\n[{}]\n", name));
+    }
+
+    // a section for a layer, starting with its parallax if it has one
+    fn layer(&mut self, name: &str, options: &Options) {
+        self.section(name);
+        self.display("parallax", options.parallax(name).map(float));
+    }
+}
+
+// a TOML basic string
+fn quote(s: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04X}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    return quoted;
+}
+
+fn strings(strings: &[String]) -> String {
+    let quoted = strings.iter().map(|s| quote(s)).collect::<Vec<String>>();
+    return format!("[{}]", quoted.join(", "));
+}
+
+// a TOML float, written with a fraction or exponent even when whole
+fn float(value: impl Debug) -> String {
+    return format!("{:?}", value);
+}
+
+fn parallax(validator: &mut Validator, section: &Section, layer: &str, options: &mut Options) {
+    if let Some(factor) = validator.float(section, "parallax", cli::PARALLAX_RANGE) {
+        options.set_parallax(cli::LayerParallax {
+            layer: layer.to_owned(),
+            factor: factor as f32,
+        });
+    }
+}
+
+struct Section<'a> {
+    table: &'a Table,
+    // dotted path of the table, empty for the top level
+    path: String,
+}
+
+impl Section<'_> {
+    fn field(&self, key: &str) -> String {
+        if self.path.is_empty() {
+            return format!("`{}`", key);
+        } else {
+            return format!("`{}.{}`", self.path, key);
+        }
+    }
+}
+
+#[derive(Default)]
+struct Validator {
+    errors: Vec<String>,
+}
+
+impl Validator {
+    fn check_keys(&mut self, section: &Section, known: &[&str]) {
+        for key in section.table.keys() {
+            if !known.contains(&key.as_str()) {
+                let expected = known
+                    .iter()
+                    .map(|known| format!("`{}`", known))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                self.errors.push(format!(
+                    "unknown field {}, expected one of {}",
+                    section.field(key),
+                    expected
+                ));
+            }
+        }
+    }
+
+    fn mismatch(&mut self, section: &Section, key: &str, expected: &str, found: &Value) {
+        self.errors.push(format!(
+            "{} should be {}, found {}",
+            section.field(key),
+            expected,
+            describe(found)
+        ));
+    }
+
+    fn section<'a>(&mut self, section: &Section<'a>, key: &str) -> Option<Section<'a>> {
+        return match section.table.get(key)? {
+            Value::Table(table) => Some(Section {
+                table,
+                path: section.field(key).trim_matches('`').to_owned(),
+            }),
+            value => {
+                self.mismatch(section, key, "a table", value);
+                None
+            }
+        };
+    }
+
+    fn boolean(&mut self, section: &Section, key: &str) -> Option<bool> {
+        return match section.table.get(key)? {
+            Value::Boolean(value) => Some(*value),
+            value => {
+                self.mismatch(section, key, "true or false", value);
+                None
+            }
+        };
+    }
+
+    fn string(&mut self, section: &Section, key: &str) -> Option<String> {
+        return match section.table.get(key)? {
+            Value::String(value) => Some(value.clone()),
+            value => {
+                self.mismatch(section, key, "a string", value);
+                None
+            }
+        };
+    }
+
+    fn strings(&mut self, section: &Section, key: &str) -> Option<Vec<String>> {
+        let value = section.table.get(key)?;
+        let strings = match value {
+            Value::Array(values) => values
+                .iter()
+                .map(|value| value.as_str().map(str::to_owned))
+                .collect::<Option<Vec<String>>>(),
+            _ => None,
+        };
+        if strings.is_none() {
+            self.mismatch(section, key, "an array of strings", value);
+        }
+
+        return strings;
+    }
+
+    fn parsed<T: FromStr<Err = String>>(
+        &mut self,
+        section: &Section,
+        key: &str,
+        expected: &str,
+    ) -> Option<T> {
+        let value = self.string(section, key)?;
+        return match value.parse() {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.errors.push(format!(
+                    "{} should be {}, found {:?}: {}",
+                    section.field(key),
+                    expected,
+                    value,
+                    error
+                ));
+                None
+            }
+        };
+    }
+
+    fn integer<T: TryFrom<i64> + Bounded + Display>(
+        &mut self,
+        section: &Section,
+        key: &str,
+    ) -> Option<T> {
+        let expected = format!("an integer between {} and {}", T::MIN, T::MAX);
+        return match section.table.get(key)? {
+            Value::Integer(value) => match T::try_from(*value) {
+                Ok(value) => Some(value),
+                Err(_) => {
+                    self.errors.push(format!(
+                        "{} should be {}, found {}",
+                        section.field(key),
+                        expected,
+                        value
+                    ));
+                    None
+                }
+            },
+            value => {
+                self.mismatch(section, key, &expected, value);
+                None
+            }
+        };
+    }
+
+    fn float(&mut self, section: &Section, key: &str, range: RangeInclusive<f64>) -> Option<f64> {
+        let expected = format!(
+            "a number between {:.1} and {:.1}",
+            range.start(),
+            range.end()
+        );
+        let value = match section.table.get(key)? {
+            Value::Float(value) => *value,
+            Value::Integer(value) => *value as f64,
+            value => {
+                self.mismatch(section, key, &expected, value);
+                return None;
+            }
+        };
+
+        if range.contains(&value) {
+            return Some(value);
+        } else {
+            self.errors.push(format!(
+                "{} should be {}, found {}",
+                section.field(key),
+                expected,
+                value
+            ));
+            return None;
+        }
+    }
+}
+
+/// Integer types a config field can hold, for the range in error messages.
+trait Bounded {
+    const MIN: Self;
+    const MAX: Self;
+}
+
+macro_rules! impl_bounded {
+    ($($t:ty),*) => {
+        $(impl Bounded for $t {
+            const MIN: Self = <$t>::MIN;
+            const MAX: Self = <$t>::MAX;
+        })*
+    };
+}
+
+impl_bounded!(i32, isize, u64, usize);
+
+fn describe(value: &Value) -> String {
+    return match value {
+        Value::String(value) => format!("the string {:?}", value),
+        Value::Integer(value) => format!("the integer {}", value),
+        Value::Float(value) => format!("the number {}", value),
+        Value::Boolean(value) => format!("{}", value),
+        Value::Datetime(value) => format!("the date {}", value),
+        Value::Array(_) => "an array".to_owned(),
+        Value::Table(_) => "a table".to_owned(),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_of(text: &str) -> String {
+        return apply(text, &mut Options::default()).unwrap_err();
+    }
+
+    #[test]
+    fn applies_valid_config() {
+        let mut options = Options::default();
+        apply(
+            "year = 2030\nwind = -2\n[tree]\nornament-density = 1\nseed = 5\n[sleigh]\nenabled = true\n",
+            &mut options,
+        )
+        .unwrap();
+
+        assert_eq!(options.year, Some(2030));
+        assert_eq!(options.wind, -2);
+        assert_eq!(options.ornament_density, 1.0);
+        assert_eq!(options.tree_seed, Some(5));
+        assert!(options.sleigh);
+    }
+
+    #[test]
+    fn applies_parallax_per_layer() {
+        let mut options = Options::default();
+        apply(
+            "scroll-speed = 2
+[snow]
+parallax = 0.5
+[tree]
+parallax = -1
+",
+            &mut options,
+        )
+        .unwrap();
+
+        assert_eq!(options.scroll_speed, 2.0);
+        assert_eq!(options.parallax("snow"), Some(0.5));
+        assert_eq!(options.parallax("tree"), Some(-1.0));
+        assert_eq!(options.parallax("sleigh"), None);
+    }
+
+    #[test]
+    fn applies_messages() {
+        let mut options = Options::default();
+        apply(
+            "messages = [\"Merry Christmas\", \"Happy {year}\"]\nmessage-interval = 2.5\n",
+            &mut options,
+        )
+        .unwrap();
+        assert_eq!(options.messages, ["Merry Christmas", "Happy {year}"]);
+        assert_eq!(options.message_interval, 2.5);
+
+        assert_eq!(
+            error_of("messages = [\"Hi\", 3]\n"),
+            "`messages` should be an array of strings, found an array"
+        );
+    }
+
+    #[test]
+    fn reads_back_what_it_saves() {
+        let mut options = Options::default();
+        apply(
+            "title = \"\\\"Snow\\\" \\u0007\"\nmessages = [\"a\", \"b\"]\nfill-color = \"bright blue\"\n\
+             [snow]\nspeed = \"0.5-2\"\nparallax = 0.5\n[snowman]\nmelt = 30\n",
+            &mut options,
+        )
+        .unwrap();
+        let saved = to_toml(&options);
+        assert!(
+            saved.contains("fill-color = \"bright blue\"\n"),
+            "{}",
+            saved
+        );
+
+        let mut loaded = Options::default();
+        apply(&saved, &mut loaded).unwrap();
+        assert_eq!(to_toml(&loaded), saved);
+        assert_eq!(loaded.title, "\"Snow\" \u{7}");
+        assert_eq!(loaded.parallax("snow"), Some(0.5));
+    }
+
+    #[test]
+    fn loads_back_the_options_it_saves_from_the_command_line() {
+        let parse = |args: &[&str]| {
+            return match Options::parse(args.iter().map(|arg| arg.to_string())) {
+                Ok(cli::Parsed::Run(options)) => Ok(*options),
+                Ok(cli::Parsed::Help) => panic!("asked for help"),
+                Err(error) => Err(error),
+            };
+        };
+
+        // at the ends of the ranges a config file takes
+        let options = parse(&[
+            "--scroll-speed=-100",
+            "--pan",
+            "100",
+            "--vignette",
+            "1",
+            "--bpm",
+            "1000",
+            "--message-interval",
+            "0.1",
+            "--parallax",
+            "tree=-10",
+            "--snowman-melt",
+            "0.1",
+            "--charge-seconds",
+            "60",
+            "--seed",
+            "42",
+            "--to",
+            "Alice \"Ali\"",
+        ])
+        .unwrap();
+        let mut loaded = Options::default();
+        apply(&to_toml(&options), &mut loaded).unwrap();
+        assert_eq!(loaded, options);
+
+        // and no further, for the file to load
+        for (args, error) in [
+            (
+                ["--scroll-speed", "500"],
+                "--scroll-speed must be between -100.0 and 100.0, got 500",
+            ),
+            (
+                ["--pan", "-1"],
+                "--pan must be between 0.0 and 100.0, got -1",
+            ),
+            (
+                ["--vignette", "2"],
+                "--vignette must be between 0.0 and 1.0, got 2",
+            ),
+        ] {
+            assert_eq!(parse(&args).map(|_| ()), Err(error.to_owned()));
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_field() {
+        assert_eq!(
+            error_of("[tree]\nornament-densty = 0.5\n"),
+            "unknown field `tree.ornament-densty`, expected one of `ornament-density`, `shape`, `seed`, `date-seed`, `snow-caps`, `smooth`, `lights-charge`, `charge-seconds`, `parallax`"
+        );
+    }
+
+    #[test]
+    fn rejects_wrong_type() {
+        assert_eq!(
+            error_of("title = 3\n"),
+            "`title` should be a string, found the integer 3"
+        );
+        assert_eq!(
+            error_of("[snow]\nground = \"yes\"\n"),
+            "`snow.ground` should be true or false, found the string \"yes\""
+        );
+    }
+
+    #[test]
+    fn rejects_out_of_range() {
+        assert_eq!(
+            error_of("[tree]\nornament-density = 1.5\n"),
+            "`tree.ornament-density` should be a number between 0.0 and 1.0, found 1.5"
+        );
+        assert_eq!(
+            error_of("[sleigh]\ninterval = -1\n"),
+            format!(
+                "`sleigh.interval` should be an integer between 0 and {}, found -1",
+                usize::MAX
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_region() {
+        assert_eq!(
+            error_of("region = \"1,2,3\"\n"),
+            "`region` should be a region like \"0,0,40,20\", found \"1,2,3\": expected four numbers"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_language() {
+        assert_eq!(
+            error_of("lang = \"xx\"\n"),
+            format!("`lang` should be one of {}, found \"xx\"", cli::languages())
+        );
+    }
+
+    #[test]
+    fn rejects_section_that_is_not_a_table() {
+        assert_eq!(
+            error_of("tree = true\n"),
+            "`tree` should be a table, found true"
+        );
+    }
+
+    #[test]
+    fn reports_every_error() {
+        assert_eq!(
+            error_of("fade = 1\n[snow]\ndrift = 0\n"),
+            "`fade` should be true or false, found the integer 1\n\
+             `snow.drift` should be true or false, found the integer 0"
+        );
+    }
+
+    #[test]
+    fn reports_syntax_errors_with_their_position() {
+        assert!(error_of("wind = = 1\n").contains("line 1, column 8"));
+    }
+}
@@ -0,0 +1,337 @@
+use std::{
+    io::{self, BufRead},
+    iter::Peekable,
+    str::Chars,
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+
+use cyber_christmas_card::Printer;
+
+use crate::json::{expect, parse_string, skip_space};
+
+const KEYS: [&str; 8] = [
+    "message",
+    "wind",
+    "snowfall",
+    "bpm",
+    "scroll-speed",
+    "pan",
+    "show",
+    "hide",
+];
+
+/// A change to the running card, from a line of JSON like
+/// `{"message": "Hi {year}", "wind": -1, "hide": ["snow"]}`. A null message,
+/// snowfall or bpm goes back to the usual one.
+#[derive(Debug, Default, PartialEq)]
+pub struct Control {
+    pub message: Option<Option<String>>,
+    pub wind: Option<isize>,
+    pub snowfall: Option<Option<f32>>,
+    pub bpm: Option<Option<f32>>,
+    pub scroll_speed: Option<f32>,
+    pub pan: Option<f32>,
+    pub show: Vec<String>,
+    pub hide: Vec<String>,
+}
+
+impl Control {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let mut chars = line.chars().peekable();
+        let value = parse_value(&mut chars)?;
+        skip_space(&mut chars);
+        if let Some(c) = chars.next() {
+            return Err(format!("unexpected `{}` after the object", c));
+        }
+        let Value::Object(fields) = value else {
+            return Err(format!("expected an object, found {}", value.describe()));
+        };
+
+        let mut control = Control::default();
+        for (key, value) in fields {
+            let mismatch = |expected: &str| {
+                return format!(
+                    "`{}` should be {}, found {}",
+                    key,
+                    expected,
+                    value.describe()
+                );
+            };
+            match (key.as_str(), &value) {
+                ("message", Value::Null) => control.message = Some(None),
+                ("message", Value::String(message)) => {
+                    control.message = Some(Some(message.clone()))
+                }
+                ("message", _) => return Err(mismatch("a string or null")),
+                ("wind", Value::Number(wind)) if wind.fract() == 0.0 => {
+                    control.wind = Some(*wind as isize);
+                }
+                ("wind", _) => return Err(mismatch("a whole number")),
+                ("snowfall", Value::Null) => control.snowfall = Some(None),
+                ("snowfall", Value::Number(snowfall)) if *snowfall >= 0.0 => {
+                    control.snowfall = Some(Some(*snowfall as f32));
+                }
+                ("snowfall", _) => return Err(mismatch("a number from 0 up or null")),
+                ("bpm", Value::Null) => control.bpm = Some(None),
+                ("bpm", Value::Number(bpm)) if *bpm > 0.0 => control.bpm = Some(Some(*bpm as f32)),
+                ("bpm", _) => return Err(mismatch("a positive number or null")),
+                ("scroll-speed", Value::Number(speed)) => {
+                    control.scroll_speed = Some(*speed as f32)
+                }
+                ("scroll-speed", _) => return Err(mismatch("a number")),
+                ("pan", Value::Number(pan)) => control.pan = Some(*pan as f32),
+                ("pan", _) => return Err(mismatch("a number")),
+                ("show" | "hide", Value::Array(items)) => {
+                    let mut layers = Vec::new();
+                    for item in items {
+                        match item {
+                            Value::String(layer) => layers.push(layer.clone()),
+                            _ => return Err(mismatch("an array of layer names")),
+                        }
+                    }
+                    if key == "show" {
+                        control.show = layers;
+                    } else {
+                        control.hide = layers;
+                    }
+                }
+                ("show" | "hide", _) => return Err(mismatch("an array of layer names")),
+                _ => {
+                    let expected = KEYS
+                        .iter()
+                        .map(|known| format!("`{}`", known))
+                        .collect::<Vec<String>>()
+                        .join(", ");
+                    return Err(format!(
+                        "unknown key `{}`, expected one of {}",
+                        key, expected
+                    ));
+                }
+            }
+        }
+
+        return Ok(control);
+    }
+
+    /// Applies the change to the card drawn by `printer`, whose frames are
+    /// the `layers` named. Nothing is changed if a layer shown or hidden is
+    /// not among them.
+    pub fn apply(&self, printer: &mut Printer, layers: &[String]) -> Result<(), String> {
+        for layer in self.show.iter().chain(&self.hide) {
+            if !layers.contains(layer) {
+                return Err(format!(
+                    "there is no {} layer, the layers are {}",
+                    layer,
+                    layers.join(", ")
+                ));
+            }
+        }
+
+        for (i, layer) in layers.iter().enumerate() {
+            if self.hide.contains(layer) {
+                printer.set_hidden(i, true);
+            } else if self.show.contains(layer) {
+                printer.set_hidden(i, false);
+            }
+        }
+        let scene = printer.scene_mut();
+        if let Some(message) = &self.message {
+            scene.message = message.clone();
+        }
+        if let Some(wind) = self.wind {
+            scene.wind = wind;
+        }
+        if let Some(snowfall) = self.snowfall {
+            scene.snowfall = snowfall;
+        }
+        if let Some(bpm) = self.bpm {
+            scene.bpm = bpm;
+        }
+        if let Some(scroll_speed) = self.scroll_speed {
+            scene.scroll_speed = scroll_speed;
+        }
+        if let Some(pan) = self.pan {
+            scene.pan_speed = pan;
+        }
+        return Ok(());
+    }
+}
+
+/// Controls read off stdin a line at a time while the card runs.
+pub struct Controls {
+    // numbered lines as they come in
+    lines: Receiver<(usize, String)>,
+    layers: Vec<String>,
+}
+
+impl Controls {
+    /// Starts reading stdin for controls of the card whose frames are the
+    /// `layers` named.
+    pub fn listen(layers: Vec<String>) -> Self {
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for (i, line) in io::stdin().lock().lines().enumerate() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if sender.send((i + 1, line)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        return Controls { lines, layers };
+    }
+
+    /// Applies every control that came in since the last call, warning on
+    /// stderr about the lines that are not valid controls and skipping them.
+    pub fn apply_pending(&self, printer: &mut Printer) {
+        for (number, line) in self.lines.try_iter() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let applied =
+                Control::parse(&line).and_then(|control| control.apply(printer, &self.layers));
+            if let Err(message) = applied {
+                eprintln!("warning: ignoring control on line {}: {}", number, message);
+            }
+        }
+    }
+}
+
+// as much of JSON as controls are written in
+#[derive(Debug)]
+enum Value {
+    Null,
+    Bool,
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    fn describe(&self) -> &'static str {
+        return match self {
+            Value::Null => "null",
+            Value::Bool => "a boolean",
+            Value::Number(_) => "a number",
+            Value::String(_) => "a string",
+            Value::Array(_) => "an array",
+            Value::Object(_) => "an object",
+        };
+    }
+}
+
+fn parse_value(chars: &mut Peekable<Chars>) -> Result<Value, String> {
+    skip_space(chars);
+    return match chars.peek().copied() {
+        Some('{') => {
+            chars.next();
+            let mut fields = Vec::new();
+            skip_space(chars);
+            if chars.next_if_eq(&'}').is_some() {
+                return Ok(Value::Object(fields));
+            }
+            loop {
+                skip_space(chars);
+                if chars.peek() != Some(&'"') {
+                    return Err("expected a key in double quotes".to_owned());
+                }
+                let key = parse_string(chars)?;
+                expect(chars, ':')?;
+                fields.push((key, parse_value(chars)?));
+                skip_space(chars);
+                match chars.next() {
+                    Some(',') => continue,
+                    Some('}') => return Ok(Value::Object(fields)),
+                    _ => return Err("expected `,` or `}` after a value".to_owned()),
+                }
+            }
+        }
+        Some('[') => {
+            chars.next();
+            let mut items = Vec::new();
+            skip_space(chars);
+            if chars.next_if_eq(&']').is_some() {
+                return Ok(Value::Array(items));
+            }
+            loop {
+                items.push(parse_value(chars)?);
+                skip_space(chars);
+                match chars.next() {
+                    Some(',') => continue,
+                    Some(']') => return Ok(Value::Array(items)),
+                    _ => return Err("expected `,` or `]` after a value".to_owned()),
+                }
+            }
+        }
+        Some('"') => Ok(Value::String(parse_string(chars)?)),
+        Some(c) if c == '-' || c.is_ascii_digit() => {
+            let mut number = String::new();
+            while let Some(c) = chars.next_if(|c| "+-.eE".contains(*c) || c.is_ascii_digit()) {
+                number.push(c);
+            }
+            number
+                .parse()
+                .map(Value::Number)
+                .map_err(|_| format!("`{}` is not a number", number))
+        }
+        Some(c) if c.is_ascii_alphabetic() => {
+            let mut word = String::new();
+            while let Some(c) = chars.next_if(|c| c.is_ascii_alphabetic()) {
+                word.push(c);
+            }
+            match word.as_str() {
+                "null" => Ok(Value::Null),
+                "true" | "false" => Ok(Value::Bool),
+                _ => Err(format!("unexpected `{}`", word)),
+            }
+        }
+        Some(c) => Err(format!("unexpected `{}`", c)),
+        None => Err("expected a value, found the end of the line".to_owned()),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_controls_and_rejects_malformed_lines() {
+        let control = Control::parse(
+            r#" {"message": "Hi \"{year}\" ❄", "wind": -2, "bpm": null, "hide": ["snow"]} "#,
+        );
+        assert_eq!(
+            control,
+            Ok(Control {
+                message: Some(Some("Hi \"{year}\" ❄".to_owned())),
+                wind: Some(-2),
+                bpm: Some(None),
+                hide: vec!["snow".to_owned()],
+                ..Control::default()
+            })
+        );
+
+        let error = |line: &str| Control::parse(line).unwrap_err();
+        assert_eq!(
+            error(r#"{"wind": 1.5}"#),
+            "`wind` should be a whole number, found a number"
+        );
+        assert_eq!(
+            error(r#"{"snowfall": -1}"#),
+            "`snowfall` should be a number from 0 up or null, found a number"
+        );
+        assert_eq!(error(r#"["wind"]"#), "expected an object, found an array");
+        assert_eq!(error(r#"{"wind": 1"#), "expected `,` or `}` after a value");
+        assert_eq!(error(r#"{"wind": 1} x"#), "unexpected `x` after the object");
+        assert_eq!(
+            error(r#"{"message": "\ud800\u0041"}"#),
+            "unpaired surrogate in a string"
+        );
+        assert!(
+            error(r#"{"speed": 1}"#).starts_with("unknown key `speed`, expected one of `message`")
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use std::{fs, path::Path};
+
+use cyber_christmas_card::Date;
+
+/// Shown instead of the blessing on the first run of the day.
+pub const FIRST_SNOW: &str = "First snow!";
+
+/// Whether the card has not been run yet on `today` by the state file at
+/// `path`, which is then updated to today. A missing or unreadable state
+/// file counts as not run yet, and failing to update it goes unnoticed but
+/// for the surprise coming again on the next run.
+pub fn is_first_run(path: &Path, today: Date) -> bool {
+    let today = today.to_string();
+    let last_run = fs::read_to_string(path).unwrap_or_default();
+    if last_run.trim() == today {
+        return false;
+    }
+
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let _ = fs::write(path, today + "\n");
+    return true;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn surprises_once_a_day() {
+        let dir = env::temp_dir().join(format!("cyber-christmas-card-{}", std::process::id()));
+        let path = dir.join("state").join("last-run");
+        let day = Date {
+            year: 2026,
+            month: 12,
+            day: 24,
+        };
+        let next_day = Date { day: 25, ..day };
+
+        assert!(is_first_run(&path, day));
+        assert!(!is_first_run(&path, day));
+        assert!(is_first_run(&path, next_day));
+
+        fs::write(&path, [0xff, 0xfe, b'\n']).unwrap();
+        assert!(is_first_run(&path, next_day));
+        assert!(!is_first_run(&path, next_day));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
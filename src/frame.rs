@@ -0,0 +1,121 @@
+use std::ops::Range;
+
+use colored::{Color, ColoredString, Colorize};
+
+use crate::{
+    scene::{Event, Ledge, Light, Scene, Surface},
+    string_width::{char_width, StringWidth},
+};
+
+/// What a frame wants to show at a single cell.
+#[derive(Clone)]
+pub enum Content {
+    /// Nothing here, lower frames show through.
+    Transparent,
+    ColoredString {
+        s: ColoredString,
+    },
+    /// Second column of a double-width glyph drawn by the cell to its left.
+    ///
+    /// It draws nothing itself: where the glyph to its left is shown, the
+    /// printer takes the column for part of it and asks no frame about it,
+    /// and anywhere else it is as good as `Transparent`. Every output leaves
+    /// such a column out rather than drawing anything there, see
+    /// `Cell::Continuation`.
+    Compensate,
+}
+
+/// A rectangle of cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bounds {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Bounds {
+    /// Bounds holding no cell at all.
+    pub const EMPTY: Bounds = Bounds {
+        x: 0,
+        y: 0,
+        width: 0,
+        height: 0,
+    };
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        return x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height;
+    }
+}
+
+/// A layer of the card. The printer asks every frame for each cell, top frame first.
+pub trait Frame {
+    fn update(&mut self, screen_width: usize, screen_height: usize);
+    fn get_content(&mut self, x: usize, y: usize) -> Content;
+
+    /// Whether the cell is solid to other frames, e.g. snow piles against it.
+    fn is_solid(&self, _x: usize, _y: usize) -> bool {
+        return false;
+    }
+
+    /// Top edges snow settles on. Frames opt in by reporting any.
+    fn solid_surfaces(&self) -> Vec<Surface> {
+        return Vec::new();
+    }
+
+    /// Surfaces snow piles up on in a ledge several rows deep, e.g. a window
+    /// sill, rather than capping them with a single row. They need not be
+    /// reported as surfaces as well.
+    fn snow_ledges(&self) -> Vec<Ledge> {
+        return Vec::new();
+    }
+
+    /// Columns the frame's clouds hang over, for snow to fall from.
+    fn clouds(&self) -> Vec<Range<usize>> {
+        return Vec::new();
+    }
+
+    /// Lights the frame gives off, e.g. candle flames.
+    fn light_sources(&self) -> Vec<Light> {
+        return Vec::new();
+    }
+
+    /// Depth of the snow lying on the ground of each column, for frames that
+    /// keep track of it.
+    fn snow_depths(&self) -> Vec<usize> {
+        return Vec::new();
+    }
+
+    /// The cells the frame may show anything at, as of its last tick. The
+    /// printer takes every other cell to be transparent without asking, which
+    /// saves a lot of calls for small or hidden frames. None means anywhere.
+    fn content_bounds(&self) -> Option<Bounds> {
+        return None;
+    }
+
+    /// Events the frame reports, seen by every frame on its next tick.
+    fn events(&self) -> Vec<Event> {
+        return Vec::new();
+    }
+
+    /// Called after every frame has been updated, once the scene is known.
+    fn tick(&mut self, _scene: &Scene) {}
+}
+
+/// Splits `s` into one content per column, padding wide glyphs with `Content::Compensate`.
+pub fn string_to_content_vec(s: &str, color: Color) -> Vec<Content> {
+    let string_width = s.width();
+    let mut content_vec = Vec::<Content>::with_capacity(string_width);
+
+    s.chars().for_each(|c| {
+        content_vec.push(Content::ColoredString {
+            s: c.to_string().color(color),
+        });
+
+        if char_width(c) == 2 {
+            content_vec.push(Content::Compensate);
+        }
+    });
+
+    return content_vec;
+}
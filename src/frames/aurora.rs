@@ -0,0 +1,107 @@
+use colored::{Color, Colorize};
+
+use crate::{
+    color,
+    frame::{Bounds, Content, Frame},
+    scene::Scene,
+};
+
+// row the middle of the band waves about, and by how many rows either way
+const MIDDLE_ROW: f32 = 2.5;
+const AMPLITUDE: f32 = 1.5;
+// rows either side of the middle of the band it fades out over
+const THICKNESS: f32 = 1.5;
+const ROWS: usize = (MIDDLE_ROW + AMPLITUDE + THICKNESS) as usize + 1;
+// radians the band waves by per column, and its curtains shimmer by
+const WAVELENGTH: f32 = 0.15;
+const SHIMMER: f32 = 0.6;
+// radians the waves move right by per update on a still night
+const DRIFT: f32 = 0.1;
+// radians the wind adds to that per update for each column it blows by
+const WIND_RIPPLE: f32 = 0.05;
+
+/// Northern lights waving across the top of the sky in curtains of green
+/// fading to cyan.
+#[derive(Default)]
+pub struct AuroraFrame {
+    frame_width: usize,
+    phase: f32,
+    wind: bool,
+}
+
+impl AuroraFrame {
+    /// Lets the scene's wind carry the waves, rippling faster on a gusty
+    /// update and running the other way when it blows left.
+    pub fn with_wind(mut self, wind: bool) -> Self {
+        self.wind = wind;
+        return self;
+    }
+
+    // how bright the aurora is at a cell, from 0 to 1
+    fn intensity_at(&self, x: usize, y: usize) -> f32 {
+        let x = x as f32;
+        let middle = MIDDLE_ROW + AMPLITUDE * (x * WAVELENGTH - self.phase).sin();
+        let band = 1.0 - (y as f32 - middle).abs() / THICKNESS;
+        let shimmer = 0.5 + 0.5 * (x * SHIMMER - 2.0 * self.phase).sin();
+        return (band * shimmer).max(0.0);
+    }
+}
+
+impl Frame for AuroraFrame {
+    fn update(&mut self, screen_width: usize, _screen_height: usize) {
+        self.frame_width = screen_width;
+    }
+
+    fn tick(&mut self, scene: &Scene) {
+        let wind = if self.wind { scene.wind as f32 } else { 0.0 };
+        self.phase = (self.phase + DRIFT + WIND_RIPPLE * wind) % std::f32::consts::TAU;
+    }
+
+    fn content_bounds(&self) -> Option<Bounds> {
+        return Some(Bounds {
+            x: 0,
+            y: 0,
+            width: self.frame_width,
+            height: ROWS,
+        });
+    }
+
+    fn get_content(&mut self, x: usize, y: usize) -> Content {
+        let intensity = self.intensity_at(x, y);
+        let glyph = match intensity {
+            i if i > 0.6 => "|",
+            i if i > 0.35 => ":",
+            i if i > 0.15 => ".",
+            _ => return Content::Transparent,
+        };
+
+        let hue = x as f32 / self.frame_width.max(1) as f32;
+        let tint = color::mix(Color::BrightGreen, Color::BrightCyan, hue);
+        return Content::ColoredString {
+            s: glyph.color(color::scale(tint, intensity)),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ripples_with_the_wind_only_when_asked_to() {
+        let mut scene = Scene::default();
+        scene.wind = 4;
+        let mut still = AuroraFrame::default();
+        let mut windswept = AuroraFrame::default().with_wind(true);
+        still.tick(&scene);
+        windswept.tick(&scene);
+        assert_eq!(still.phase, DRIFT);
+        assert_eq!(windswept.phase, DRIFT + 4.0 * WIND_RIPPLE);
+
+        // blowing left hard enough runs the waves the other way
+        scene.wind = -4;
+        let mut windswept = AuroraFrame::default().with_wind(true);
+        windswept.tick(&scene);
+        assert!(windswept.phase < 0.0);
+    }
+}
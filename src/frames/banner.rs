@@ -0,0 +1,93 @@
+use colored::Color;
+
+use crate::{
+    frame::{string_to_content_vec, Bounds, Content, Frame},
+    scene::{Event, Scene},
+    string_width::StringWidth,
+};
+
+const COLORS: [Color; 6] = [
+    Color::BrightRed,
+    Color::BrightYellow,
+    Color::BrightGreen,
+    Color::BrightCyan,
+    Color::BrightBlue,
+    Color::BrightMagenta,
+];
+
+/// A line of text across the middle of the screen, unfurled by the first
+/// `Event::Midnight`, its letters cycling through the colors of the rainbow.
+pub struct BannerFrame {
+    text: String,
+    frame_width: usize,
+    frame_height: usize,
+    shown: bool,
+    phase: usize,
+    contents: Vec<Content>,
+}
+
+impl BannerFrame {
+    pub fn new(text: &str) -> Self {
+        return BannerFrame {
+            text: text.to_owned(),
+            frame_width: 0,
+            frame_height: 0,
+            shown: false,
+            phase: 0,
+            contents: Vec::new(),
+        };
+    }
+}
+
+impl Frame for BannerFrame {
+    fn update(&mut self, screen_width: usize, screen_height: usize) {
+        self.frame_width = screen_width;
+        self.frame_height = screen_height;
+        self.phase = self.phase.wrapping_add(1);
+    }
+
+    fn tick(&mut self, scene: &Scene) {
+        if scene.events().contains(&Event::Midnight) {
+            self.shown = true;
+        }
+        if !self.shown {
+            return;
+        }
+
+        self.contents = self
+            .text
+            .chars()
+            .enumerate()
+            .flat_map(|(i, c)| {
+                let color = COLORS[(i + self.phase) % COLORS.len()];
+                return string_to_content_vec(&c.to_string(), color);
+            })
+            .collect();
+    }
+
+    fn content_bounds(&self) -> Option<Bounds> {
+        if !self.shown {
+            return Some(Bounds::EMPTY);
+        }
+
+        let width = self.text.width();
+        return Some(Bounds {
+            x: self.frame_width.saturating_sub(width) / 2,
+            y: self.frame_height / 2,
+            width,
+            height: 1,
+        });
+    }
+
+    fn get_content(&mut self, x: usize, y: usize) -> Content {
+        if !self.shown || y != self.frame_height / 2 {
+            return Content::Transparent;
+        }
+
+        let x_offset = self.frame_width.saturating_sub(self.text.width()) / 2;
+        return match x.checked_sub(x_offset).and_then(|x| self.contents.get(x)) {
+            Some(content) => content.clone(),
+            None => Content::Transparent,
+        };
+    }
+}
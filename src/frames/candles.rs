@@ -0,0 +1,108 @@
+use colored::{Color, Colorize};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    frame::{Content, Frame},
+    scene::Light,
+};
+
+const CANDLE_HEIGHT: usize = 3;
+// how far the glow of a flame reaches, in columns
+const GLOW_RADIUS: f32 = 24.0;
+
+/// A pair of candles standing on the ground either side of the tree, their
+/// flames flickering and lighting up the scene around them.
+pub struct CandlesFrame {
+    rng: StdRng,
+    frame_width: usize,
+    frame_height: usize,
+    flames: [(char, Color); 2],
+}
+
+impl CandlesFrame {
+    /// Makes the flicker reproducible instead of seeding it from entropy.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        return self;
+    }
+
+    fn candle_xs(&self) -> [usize; 2] {
+        return [self.frame_width / 4, self.frame_width * 3 / 4];
+    }
+
+    fn flame_y(&self) -> Option<usize> {
+        return self.frame_height.checked_sub(CANDLE_HEIGHT + 1);
+    }
+}
+
+impl Default for CandlesFrame {
+    fn default() -> Self {
+        return CandlesFrame {
+            rng: StdRng::from_entropy(),
+            frame_width: 0,
+            frame_height: 0,
+            flames: [('(', Color::Yellow); 2],
+        };
+    }
+}
+
+impl Frame for CandlesFrame {
+    fn update(&mut self, screen_width: usize, screen_height: usize) {
+        self.frame_width = screen_width;
+        self.frame_height = screen_height;
+
+        for flame in self.flames.iter_mut() {
+            let glyph = match self.rng.gen_range(0..3) {
+                0 => '(',
+                1 => ')',
+                _ => '^',
+            };
+            let color = match self.rng.gen_range(0..4) {
+                0 => Color::Red,
+                1 => Color::BrightYellow,
+                _ => Color::Yellow,
+            };
+            *flame = (glyph, color);
+        }
+    }
+
+    fn light_sources(&self) -> Vec<Light> {
+        let Some(flame_y) = self.flame_y() else {
+            return Vec::new();
+        };
+
+        return self
+            .candle_xs()
+            .into_iter()
+            .zip(self.flames)
+            .map(|(x, (_, color))| Light {
+                x,
+                y: flame_y,
+                radius: GLOW_RADIUS,
+                color,
+            })
+            .collect();
+    }
+
+    fn get_content(&mut self, x: usize, y: usize) -> Content {
+        let Some(flame_y) = self.flame_y() else {
+            return Content::Transparent;
+        };
+        let Some(candle) = self.candle_xs().iter().position(|candle_x| *candle_x == x) else {
+            return Content::Transparent;
+        };
+
+        if y == flame_y {
+            let (glyph, color) = self.flames[candle];
+            return Content::ColoredString {
+                s: glyph.to_string().color(color),
+            };
+        } else if y > flame_y {
+            return Content::ColoredString {
+                s: "|".bright_white(),
+            };
+        } else {
+            return Content::Transparent;
+        }
+    }
+}
@@ -0,0 +1,931 @@
+use std::{fmt, str::FromStr, time::Duration};
+
+use colored::{Color, ColoredString, Colorize};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    blessing::DEFAULT_BLESSING_TEMPLATE,
+    color,
+    date::Date,
+    frame::{string_to_content_vec, Bounds, Content, Frame},
+    scene::{Light, Scene, Surface},
+    string_width::StringWidth,
+};
+
+const BROWN: Color = Color::TrueColor {
+    r: 139,
+    g: 69,
+    b: 19,
+};
+
+// the tree is laid out top to bottom as leaves, trunk, a blank row and the blessing
+const LEAF_HEIGHT: usize = 10;
+const TRUNK_HEIGHT: usize = 2;
+const BLANK_HEIGHT: usize = 1;
+const BLESSING_HEIGHT: usize = 1;
+const TRUNK_WIDTH: usize = 3;
+// how far the glow of an ornament reaches, in columns
+const ORNAMENT_GLOW_RADIUS: f32 = 3.0;
+// how bright the softened leaves on the edges of a smooth tree are
+const SMOOTH_EDGE_BRIGHTNESS: f32 = 0.6;
+// brightness of an ornament not yet lit while the lights charge
+const UNLIT_BRIGHTNESS: f32 = 0.25;
+// fewest leaf rows a shrinking tree keeps before giving up on fitting
+const MIN_LEAF_HEIGHT: usize = 4;
+// shown instead of a tree that cannot shrink to fit
+const TOO_SMALL: &str = "Too small for the tree";
+// leaf rows in each tier of a tiered tree
+const TIER_HEIGHT: usize = 4;
+// caret behind the typed part of the blessing while it is being typed out
+const CARET: &str = "▌";
+// how long a rotated blessing takes to fade in, at most half its showing
+const BLESSING_FADE: Duration = Duration::from_secs(1);
+
+/// Default chance of a leaf being an ornament, about one leaf in eleven.
+pub const DEFAULT_ORNAMENT_DENSITY: f64 = 1.0 / 11.0;
+
+/// Outline of the leaves of the tree.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TreeShape {
+    /// A single triangle, one leaf wider on each side every row.
+    #[default]
+    Triangle,
+    /// A stack of triangles, each starting narrower than the one above ends,
+    /// like a pine.
+    Tiered,
+    /// A triangle with its sides bulging out into a rounded crown.
+    Rounded,
+}
+
+impl FromStr for TreeShape {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s {
+            "triangle" => Ok(TreeShape::Triangle),
+            "tiered" => Ok(TreeShape::Tiered),
+            "rounded" => Ok(TreeShape::Rounded),
+            _ => Err("expected triangle, tiered or rounded".to_owned()),
+        };
+    }
+}
+
+impl fmt::Display for TreeShape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return f.write_str(match self {
+            TreeShape::Triangle => "triangle",
+            TreeShape::Tiered => "tiered",
+            TreeShape::Rounded => "rounded",
+        });
+    }
+}
+
+/// Where the blessing sits on its row.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BlessingAlign {
+    /// Against the left edge of the screen.
+    Left,
+    /// In the middle, under the tree.
+    #[default]
+    Center,
+    /// Against the right edge of the screen.
+    Right,
+}
+
+impl FromStr for BlessingAlign {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s {
+            "left" => Ok(BlessingAlign::Left),
+            "center" => Ok(BlessingAlign::Center),
+            "right" => Ok(BlessingAlign::Right),
+            _ => Err("expected left, center or right".to_owned()),
+        };
+    }
+}
+
+impl fmt::Display for BlessingAlign {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return f.write_str(match self {
+            BlessingAlign::Left => "left",
+            BlessingAlign::Center => "center",
+            BlessingAlign::Right => "right",
+        });
+    }
+}
+
+pub struct ChristmasTreeFrame {
+    rng: StdRng,
+    frame_width: usize,
+    frame_height: usize,
+    ornament_density: f64,
+    blessing_templates: Vec<String>,
+    year: i32,
+    blessings: Vec<String>,
+    // how long each of several blessings is shown before the next
+    blessing_interval: Duration,
+    blessing_fade: bool,
+    // the blessing showing and how far it has faded in
+    blessing: String,
+    blessing_brightness: f32,
+    blessing_shown: bool,
+    blessing_align: BlessingAlign,
+    // columns the blessing is moved right of where its alignment puts it
+    blessing_offset: isize,
+    typewriter: bool,
+    // characters of the blessing typed out so far, and whether the caret is lit
+    typed: usize,
+    caret_lit: bool,
+    snow_caps: bool,
+    leaf_height: usize,
+    shape: TreeShape,
+    fill: bool,
+    smooth: bool,
+    // whether the lit ornaments are reported as lights for the snow to reflect
+    reflections: bool,
+    shrink: bool,
+    // whether the blank row above the blessing has been given up to fit
+    tight: bool,
+    too_small: bool,
+    // whether the ornaments are hung afresh on every update
+    twinkle: bool,
+    // beat the ornaments were last hung on, if keeping to a tempo
+    beat: Option<u64>,
+    // color of the ornament hanging on each leaf of each leaf row, if any
+    ornaments: Vec<Vec<Option<Color>>>,
+    // how long the lights take to come on from the bottom up, if not at once
+    lights_charge: Option<Duration>,
+    // how far up the tree the lights have come on, from 0 to 1
+    charged: f32,
+}
+
+impl ChristmasTreeFrame {
+    /// Sets the chance of a leaf being an ornament, from `0.0` (a bare tree)
+    /// to `1.0` (nothing but ornaments). Any other value, NaN included, is an error.
+    pub fn with_ornament_density(mut self, ornament_density: f64) -> Result<Self, String> {
+        if !(0.0..=1.0).contains(&ornament_density) {
+            return Err(format!(
+                "ornament density must be from 0 to 1, got {}",
+                ornament_density
+            ));
+        }
+
+        self.ornament_density = ornament_density;
+        return Ok(self);
+    }
+
+    /// Powers the lights on in a wave sweeping up the tree over `duration` by
+    /// the scene's clock, each ornament lighting in turn before they all
+    /// twinkle as usual. Fails if `duration` is zero.
+    pub fn with_lights_charge(mut self, duration: Duration) -> Result<Self, String> {
+        if duration.is_zero() {
+            return Err("the lights must take longer than zero to charge".to_owned());
+        }
+
+        self.lights_charge = Some(duration);
+        self.charged = 0.0;
+        return Ok(self);
+    }
+
+    /// Makes the ornaments reproducible instead of seeding them from entropy.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        return self;
+    }
+
+    /// Whether the ornaments are hung afresh on every update, twinkling. On
+    /// by default; off they stay put until the tree changes size, or until
+    /// the next beat when keeping to a tempo.
+    pub fn with_twinkle(mut self, twinkle: bool) -> Self {
+        self.twinkle = twinkle;
+        return self;
+    }
+
+    /// Wishes a merry christmas for `year` instead of the current year.
+    pub fn with_year(mut self, year: i32) -> Self {
+        self.year = year;
+        self.blessings = self
+            .blessing_templates
+            .iter()
+            .map(|template| template.replace("{year}", &year.to_string()))
+            .collect();
+        self.blessing = self.blessings[0].clone();
+        return self;
+    }
+
+    /// Shows another blessing, `{year}` being replaced by the year.
+    pub fn with_blessing_template(self, blessing_template: &str) -> Self {
+        return self.with_blessing_templates(&[blessing_template.to_owned()]);
+    }
+
+    /// Shows each of several blessings in turn, for `with_blessing_interval`
+    /// each, `{year}` being replaced by the year. An empty list leaves the
+    /// blessing as it is.
+    pub fn with_blessing_templates(mut self, blessing_templates: &[String]) -> Self {
+        if blessing_templates.is_empty() {
+            return self;
+        }
+
+        self.blessing_templates = blessing_templates.to_vec();
+        let year = self.year;
+        return self.with_year(year);
+    }
+
+    /// Sets how long each of several blessings is shown by the scene's clock,
+    /// five seconds by default. Fails if `interval` is zero.
+    pub fn with_blessing_interval(mut self, interval: Duration) -> Result<Self, String> {
+        if interval.is_zero() {
+            return Err("blessing interval must be longer than zero".to_owned());
+        }
+
+        self.blessing_interval = interval;
+        return Ok(self);
+    }
+
+    /// Fades each of several blessings in as it takes its turn.
+    pub fn with_blessing_fade(mut self, blessing_fade: bool) -> Self {
+        self.blessing_fade = blessing_fade;
+        return self;
+    }
+
+    /// Shows the blessing under the tree, or just the tree, centred on its own.
+    pub fn with_blessing_shown(mut self, blessing_shown: bool) -> Self {
+        self.blessing_shown = blessing_shown;
+        return self;
+    }
+
+    /// Aligns the blessing to `align`, then moves it `offset` columns right,
+    /// or left if negative, as far as it can go while staying on screen.
+    pub fn with_blessing_align(mut self, align: BlessingAlign, offset: isize) -> Self {
+        self.blessing_align = align;
+        self.blessing_offset = offset;
+        return self;
+    }
+
+    /// Types the blessing out a character per update behind a blinking
+    /// caret, starting over whenever another of several blessings takes its turn.
+    pub fn with_typewriter(mut self, typewriter: bool) -> Self {
+        self.typewriter = typewriter;
+        return self;
+    }
+
+    // column the blessing starts at, going by how wide it is on screen
+    fn blessing_left(&self) -> usize {
+        let room = self.frame_width.saturating_sub(self.blessing.width());
+        let aligned = match self.blessing_align {
+            BlessingAlign::Left => 0,
+            BlessingAlign::Center => room / 2,
+            BlessingAlign::Right => room,
+        };
+        return aligned
+            .saturating_add_signed(self.blessing_offset)
+            .min(room);
+    }
+
+    // starts typing `blessing` out anew unless it is already showing
+    fn show_blessing(&mut self, blessing: String) {
+        if blessing != self.blessing {
+            self.blessing = blessing;
+            self.typed = 0;
+        }
+    }
+
+    fn rotate_blessings(&mut self, time: Duration) {
+        let turns = time.as_secs_f32() / self.blessing_interval.as_secs_f32();
+        self.show_blessing(self.blessings[turns as usize % self.blessings.len()].clone());
+
+        let fade = BLESSING_FADE.min(self.blessing_interval / 2);
+        self.blessing_brightness = if self.blessing_fade {
+            (turns.fract() * self.blessing_interval.as_secs_f32() / fade.as_secs_f32()).min(1.0)
+        } else {
+            1.0
+        };
+    }
+
+    /// Lets falling snow settle on the branches and on top of the blessing.
+    pub fn with_snow_caps(mut self, snow_caps: bool) -> Self {
+        self.snow_caps = snow_caps;
+        return self;
+    }
+
+    /// Grows the tree to fill the screen instead of keeping it at its usual
+    /// size. It never grows wider than the screen nor shrinks below its usual size.
+    pub fn with_fill(mut self, fill: bool) -> Self {
+        self.fill = fill;
+        return self;
+    }
+
+    /// Reports the lit ornaments as lights of the scene, for the ground snow
+    /// to reflect. Off by default, leaving them out of the scene's lighting
+    /// and so of the vignette.
+    pub fn with_reflections(mut self, reflections: bool) -> Self {
+        self.reflections = reflections;
+        return self;
+    }
+
+    /// Softens the jagged steps of the tree's outline by drawing the leaves
+    /// on its edges as dimmer, smaller ones.
+    pub fn with_smooth(mut self, smooth: bool) -> Self {
+        self.smooth = smooth;
+        return self;
+    }
+
+    /// Shrinks the tree to fit a screen too small for its usual size, giving
+    /// up the blank row above the blessing first and then leaf rows. A
+    /// screen too small for even a few leaf rows gets a note saying so instead.
+    pub fn with_shrink(mut self, shrink: bool) -> Self {
+        self.shrink = shrink;
+        return self;
+    }
+
+    /// Gives the tree another outline than the usual triangle.
+    pub fn with_shape(mut self, shape: TreeShape) -> Self {
+        self.shape = shape;
+        return self;
+    }
+
+    fn height(&self) -> usize {
+        return self.leaf_height + TRUNK_HEIGHT + self.blessing_height();
+    }
+
+    // rows taken up under the trunk by the blessing, if shown
+    fn blessing_height(&self) -> usize {
+        if !self.blessing_shown {
+            return 0;
+        }
+        return self.gap() + BLESSING_HEIGHT;
+    }
+
+    // rows between the trunk and the blessing
+    fn gap(&self) -> usize {
+        if self.tight {
+            return 0;
+        }
+        return BLANK_HEIGHT;
+    }
+
+    // leaf rows fitting a `screen_width` x `screen_height` screen alongside
+    // the trunk and blessing
+    fn fitting_leaf_height(&self, screen_width: usize, screen_height: usize) -> usize {
+        return screen_height
+            .saturating_sub(TRUNK_HEIGHT + self.blessing_height())
+            .min(screen_width.div_ceil(2));
+    }
+
+    // (width, offset) of the leaf row `row`, kept odd wide to stay centred
+    fn leaf_row(&self, row: usize) -> (usize, usize) {
+        let half = match self.shape {
+            TreeShape::Triangle => row,
+            // each tier starts two leaves narrower than the last one ended
+            TreeShape::Tiered => row - 2 * (row / TIER_HEIGHT),
+            TreeShape::Rounded => {
+                let last = self.leaf_height.saturating_sub(1).max(1) as f32;
+                (last * (row as f32 / last).sqrt()).round() as usize
+            }
+        };
+        let width = 2 * half + 1;
+        return (width, self.frame_width.saturating_sub(width) / 2);
+    }
+
+    fn get_leaf_color(&mut self) -> Color {
+        return match self.rng.gen_range(0..=5) {
+            0 => Color::Red,
+            1 => Color::Green,
+            2 => Color::Yellow,
+            3 => Color::Blue,
+            4 => Color::Magenta,
+            5 => Color::Cyan,
+            _ => Color::White,
+        };
+    }
+
+    fn hang_ornaments(&mut self) {
+        self.ornaments = (0..self.leaf_height)
+            .map(|row| {
+                let (width, _) = self.leaf_row(row);
+                let mut ornaments = Vec::with_capacity(width);
+                for _ in 0..width {
+                    if self.rng.gen_bool(self.ornament_density) {
+                        ornaments.push(Some(self.get_leaf_color()));
+                    } else {
+                        ornaments.push(None);
+                    }
+                }
+                return ornaments;
+            })
+            .collect();
+    }
+
+    // whether the ornament on a leaf has come on yet, those lower down and
+    // further left on their row coming on first
+    fn is_lit(&self, row: usize, leaf: usize) -> bool {
+        let width = self.ornaments[row].len().max(1) as f32;
+        let rows_below = self.leaf_height.saturating_sub(row + 1) as f32;
+        let turn = (rows_below + leaf as f32 / width) / self.leaf_height.max(1) as f32;
+        return turn <= self.charged;
+    }
+
+    fn get_leaf(&self, row: usize, leaf: usize) -> ColoredString {
+        // the tip is left sharp, being all edge
+        let width = self.ornaments[row].len();
+        let edge = self.smooth && row > 0 && (leaf == 0 || leaf + 1 == width);
+        return match self.ornaments[row][leaf] {
+            Some(color) if self.is_lit(row, leaf) => "o".color(color),
+            Some(color) => "o".color(color::scale(color, UNLIT_BRIGHTNESS)),
+            None if edge => ".".color(color::scale(Color::Green, SMOOTH_EDGE_BRIGHTNESS)),
+            None => "*".green(),
+        };
+    }
+}
+
+impl Default for ChristmasTreeFrame {
+    fn default() -> Self {
+        return ChristmasTreeFrame {
+            rng: StdRng::from_entropy(),
+            frame_width: 0,
+            frame_height: 0,
+            ornament_density: DEFAULT_ORNAMENT_DENSITY,
+            blessing_templates: vec![DEFAULT_BLESSING_TEMPLATE.to_owned()],
+            year: 0,
+            blessings: Vec::new(),
+            blessing_interval: Duration::from_secs(5),
+            blessing_fade: false,
+            blessing: String::new(),
+            blessing_brightness: 1.0,
+            blessing_shown: true,
+            blessing_align: BlessingAlign::Center,
+            blessing_offset: 0,
+            typewriter: false,
+            typed: 0,
+            caret_lit: false,
+            snow_caps: false,
+            leaf_height: LEAF_HEIGHT,
+            shape: TreeShape::Triangle,
+            fill: false,
+            smooth: false,
+            reflections: false,
+            shrink: false,
+            tight: false,
+            too_small: false,
+            twinkle: true,
+            beat: None,
+            ornaments: Vec::new(),
+            lights_charge: None,
+            charged: 1.0,
+        }
+        .with_year(Date::today().year);
+    }
+}
+
+impl Frame for ChristmasTreeFrame {
+    fn update(&mut self, screen_width: usize, screen_height: usize) {
+        if self.frame_width != screen_width || self.frame_height != screen_height {
+            self.frame_width = screen_width;
+            self.frame_height = screen_height;
+        }
+        if self.fill {
+            let fitting = self.fitting_leaf_height(screen_width, screen_height);
+            self.leaf_height = fitting.max(LEAF_HEIGHT);
+        }
+        if self.shrink {
+            self.tight = false;
+            let mut fitting = self.fitting_leaf_height(screen_width, screen_height);
+            if fitting < LEAF_HEIGHT {
+                self.tight = true;
+                fitting = self.fitting_leaf_height(screen_width, screen_height);
+            }
+            self.too_small = fitting < MIN_LEAF_HEIGHT;
+            self.leaf_height = if self.too_small {
+                0
+            } else {
+                fitting.min(self.leaf_height.max(LEAF_HEIGHT))
+            };
+        }
+
+        if self.typewriter && self.typed < self.blessing.chars().count() {
+            self.typed += 1;
+            self.caret_lit = !self.caret_lit;
+        }
+
+        // keeping to a tempo the ornaments blink on the beat instead
+        if (self.twinkle && self.beat.is_none()) || self.ornaments.len() != self.leaf_height {
+            self.hang_ornaments();
+        }
+    }
+
+    fn tick(&mut self, scene: &Scene) {
+        match &scene.message {
+            Some(message) => {
+                self.show_blessing(message.replace("{year}", &self.year.to_string()));
+                self.blessing_brightness = 1.0;
+            }
+            None if self.blessings.len() > 1 => self.rotate_blessings(scene.time),
+            None => self.show_blessing(self.blessings[0].clone()),
+        }
+        if let Some(lights_charge) = self.lights_charge {
+            self.charged = (scene.time.as_secs_f32() / lights_charge.as_secs_f32()).min(1.0);
+        }
+
+        let Some(beat) = scene.beat() else {
+            self.beat = None;
+            return;
+        };
+
+        let beat = beat as u64;
+        if self.beat != Some(beat) {
+            self.beat = Some(beat);
+            self.hang_ornaments();
+        }
+    }
+
+    fn light_sources(&self) -> Vec<Light> {
+        if !self.reflections {
+            return Vec::new();
+        }
+
+        let y_offset = self.frame_height.saturating_sub(self.height()) / 2;
+        let mut lights = Vec::new();
+
+        for (row, ornaments) in self.ornaments.iter().enumerate() {
+            let leaf_offset = self.frame_width.saturating_sub(ornaments.len()) / 2;
+            for (leaf, ornament) in ornaments.iter().enumerate() {
+                if let Some(color) = ornament.filter(|_| self.is_lit(row, leaf)) {
+                    lights.push(Light {
+                        x: leaf_offset + leaf,
+                        y: y_offset + row,
+                        radius: ORNAMENT_GLOW_RADIUS,
+                        color,
+                    });
+                }
+            }
+        }
+
+        return lights;
+    }
+
+    fn is_solid(&self, x: usize, y: usize) -> bool {
+        if self.too_small {
+            return false;
+        }
+        let y_offset = self.frame_height.saturating_sub(self.height()) / 2;
+        if y < y_offset {
+            return false;
+        }
+
+        let (width, offset) = if y - y_offset < self.leaf_height {
+            self.leaf_row(y - y_offset)
+        } else if y - y_offset - self.leaf_height < TRUNK_HEIGHT {
+            (
+                TRUNK_WIDTH,
+                self.frame_width.saturating_sub(TRUNK_WIDTH) / 2,
+            )
+        } else {
+            return false;
+        };
+
+        return x >= offset && x < offset + width;
+    }
+
+    fn solid_surfaces(&self) -> Vec<Surface> {
+        if !self.snow_caps || self.too_small {
+            return Vec::new();
+        }
+
+        let y_offset = self.frame_height.saturating_sub(self.height()) / 2;
+        let mut surfaces = Vec::new();
+
+        // the leaves of each row sticking out past the ends of the row above
+        for row in 0..self.leaf_height {
+            let (leaf_width, leaf_offset) = self.leaf_row(row);
+            if row == 0 {
+                surfaces.push(Surface {
+                    x: leaf_offset,
+                    y: y_offset,
+                    width: leaf_width,
+                });
+                continue;
+            }
+
+            let (above_width, above_offset) = self.leaf_row(row - 1);
+            let overhang = above_offset.saturating_sub(leaf_offset);
+            if overhang == 0 {
+                continue;
+            }
+            surfaces.push(Surface {
+                x: leaf_offset,
+                y: y_offset + row,
+                width: overhang,
+            });
+            surfaces.push(Surface {
+                x: above_offset + above_width,
+                y: y_offset + row,
+                width: overhang,
+            });
+        }
+
+        if self.blessing_shown {
+            surfaces.push(Surface {
+                x: self.blessing_left(),
+                y: y_offset + self.leaf_height + TRUNK_HEIGHT + self.gap(),
+                width: self.blessing.width(),
+            });
+        }
+
+        return surfaces;
+    }
+
+    fn content_bounds(&self) -> Option<Bounds> {
+        if self.too_small {
+            return Some(Bounds {
+                x: 0,
+                y: self.frame_height / 2,
+                width: self.frame_width,
+                height: 1,
+            });
+        }
+
+        return Some(Bounds {
+            x: 0,
+            y: self.frame_height.saturating_sub(self.height()) / 2,
+            width: self.frame_width,
+            height: self.height(),
+        });
+    }
+
+    fn get_content(&mut self, x: usize, y: usize) -> Content {
+        if self.too_small {
+            let offset = self.frame_width.saturating_sub(TOO_SMALL.len()) / 2;
+            return match x.checked_sub(offset).and_then(|x| TOO_SMALL.get(x..x + 1)) {
+                Some(c) if y == self.frame_height / 2 => Content::ColoredString { s: c.red() },
+                _ => Content::Transparent,
+            };
+        }
+
+        // christmas tree only shows on the middle rows
+        let y_offset = self.frame_height.saturating_sub(self.height()) / 2;
+        if y < y_offset || y >= y_offset + self.height() {
+            return Content::Transparent;
+        }
+
+        // leaf part
+        if y - y_offset < self.leaf_height {
+            let (leaf_width, leaf_offset) = self.leaf_row(y - y_offset);
+            if x < leaf_offset || x >= leaf_offset + leaf_width {
+                return Content::Transparent;
+            } else {
+                return Content::ColoredString {
+                    s: self.get_leaf(y - y_offset, x - leaf_offset),
+                };
+            }
+        }
+
+        // trunk part
+        if y - y_offset - self.leaf_height < TRUNK_HEIGHT {
+            let trunk = "mWm".to_owned();
+            let trunk_vec = string_to_content_vec(&trunk, BROWN);
+            let trunk_offset = self.frame_width.saturating_sub(TRUNK_WIDTH) / 2;
+            if x < trunk_offset || x >= trunk_offset + TRUNK_WIDTH {
+                return Content::Transparent;
+            } else {
+                return trunk_vec[x - trunk_offset].clone();
+            }
+        }
+
+        // blank part
+        if y - y_offset - self.leaf_height - TRUNK_HEIGHT < self.gap() {
+            return Content::Transparent;
+        }
+
+        // blessing part
+        if y - y_offset - self.leaf_height - TRUNK_HEIGHT - self.gap() < BLESSING_HEIGHT {
+            let blessing_color = if self.blessing_brightness < 1.0 {
+                color::scale(Color::Red, self.blessing_brightness)
+            } else {
+                Color::Red
+            };
+            // laid out as a whole so it stays put while being typed out
+            let blessing_offset = self.blessing_left();
+            let typed = if self.typewriter {
+                self.blessing.chars().take(self.typed).collect::<String>()
+            } else {
+                self.blessing.clone()
+            };
+            let typing = typed.len() < self.blessing.len();
+
+            let blessing_vec = string_to_content_vec(&typed, blessing_color);
+            return match x.checked_sub(blessing_offset) {
+                Some(x) if x < blessing_vec.len() => blessing_vec[x].clone(),
+                // right after the last character typed, however wide it is
+                Some(x) if typing && self.caret_lit && x == blessing_vec.len() => {
+                    Content::ColoredString {
+                        s: CARET.color(blessing_color),
+                    }
+                }
+                _ => Content::Transparent,
+            };
+        }
+
+        return Content::Transparent;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_impossible_ornament_densities() {
+        let error = ChristmasTreeFrame::default()
+            .with_ornament_density(-0.1)
+            .err();
+        assert_eq!(
+            error.as_deref(),
+            Some("ornament density must be from 0 to 1, got -0.1")
+        );
+        assert!(ChristmasTreeFrame::default()
+            .with_ornament_density(f64::NAN)
+            .is_err());
+        assert!(ChristmasTreeFrame::default()
+            .with_ornament_density(1.0)
+            .is_ok());
+    }
+
+    #[test]
+    fn puts_the_caret_after_a_wide_character() {
+        let mut tree = ChristmasTreeFrame::default()
+            .with_blessing_template("雪x")
+            .with_typewriter(true);
+        let blessing_y = 13;
+        tree.update(20, 15);
+        assert!(
+            matches!(tree.get_content(8, blessing_y), Content::ColoredString { s } if s.input == "雪")
+        );
+        assert!(matches!(
+            tree.get_content(9, blessing_y),
+            Content::Compensate
+        ));
+        assert!(
+            matches!(tree.get_content(10, blessing_y), Content::ColoredString { s } if s.input == CARET)
+        );
+
+        // the caret is gone once all of it is typed out
+        tree.update(20, 15);
+        assert!(
+            matches!(tree.get_content(10, blessing_y), Content::ColoredString { s } if s.input == "x")
+        );
+        assert!(matches!(
+            tree.get_content(11, blessing_y),
+            Content::Transparent
+        ));
+    }
+
+    #[test]
+    fn softens_the_edges_of_a_smooth_tree() {
+        let mut tree = ChristmasTreeFrame::default()
+            .with_ornament_density(0.0)
+            .unwrap()
+            .with_smooth(true);
+        tree.update(20, 14);
+        let leaf = |tree: &mut ChristmasTreeFrame, x| match tree.get_content(x, 2) {
+            Content::ColoredString { s } => s.input,
+            _ => panic!("no leaf at {}", x),
+        };
+        // the tip stays sharp, and the third row runs from 7 to 11
+        assert!(matches!(tree.get_content(9, 0), Content::ColoredString { s } if s.input == "*"));
+        assert_eq!(
+            (7..12).map(|x| leaf(&mut tree, x)).collect::<Vec<_>>(),
+            [".", "*", "*", "*", "."]
+        );
+    }
+
+    #[test]
+    fn centres_the_tree_alone_without_a_blessing() {
+        let mut tree = ChristmasTreeFrame::default().with_blessing_shown(false);
+        tree.update(20, 14);
+        let bounds = tree.content_bounds().unwrap();
+        assert_eq!((bounds.y, bounds.height), (1, 12));
+        // the trunk is the bottom row, with nothing under it
+        assert!(matches!(tree.get_content(9, 12), Content::ColoredString { s } if s.input == "W"));
+        assert!(tree.solid_surfaces().iter().all(|surface| surface.y < 12));
+        for x in 0..20 {
+            assert!(matches!(tree.get_content(x, 13), Content::Transparent));
+        }
+    }
+
+    #[test]
+    fn shrinks_to_fit_until_too_small() {
+        let mut tree = ChristmasTreeFrame::default().with_shrink(true);
+        tree.update(40, 14);
+        assert_eq!((tree.leaf_height, tree.height()), (LEAF_HEIGHT, 14));
+
+        // the blank row goes first, then leaf rows
+        tree.update(40, 13);
+        assert_eq!((tree.leaf_height, tree.height()), (LEAF_HEIGHT, 13));
+        tree.update(40, 8);
+        assert_eq!((tree.leaf_height, tree.height()), (5, 8));
+        tree.update(9, 20);
+        assert_eq!(tree.leaf_height, 5);
+
+        tree.update(40, 6);
+        assert!(tree.too_small);
+        assert!(matches!(tree.get_content(9, 3), Content::ColoredString { s } if s.input == "T"));
+
+        // and grows back once there is room again
+        tree.update(40, 20);
+        assert_eq!((tree.too_small, tree.leaf_height), (false, LEAF_HEIGHT));
+    }
+
+    #[test]
+    fn keeps_an_aligned_blessing_on_screen() {
+        let mut tree = ChristmasTreeFrame::default()
+            .with_blessing_template("雪x")
+            .with_blessing_align(BlessingAlign::Right, 0);
+        tree.update(20, 15);
+        // three columns wide, the wide character's second half included
+        assert_eq!(tree.blessing_left(), 17);
+        assert!(matches!(tree.get_content(19, 13), Content::ColoredString { s } if s.input == "x"));
+
+        tree = tree.with_blessing_align(BlessingAlign::Right, 5);
+        assert_eq!(tree.blessing_left(), 17);
+        tree = tree.with_blessing_align(BlessingAlign::Left, -5);
+        assert_eq!(tree.blessing_left(), 0);
+        tree = tree.with_blessing_align(BlessingAlign::Center, -2);
+        assert_eq!(tree.blessing_left(), 6);
+    }
+
+    #[test]
+    fn charges_the_lights_from_the_bottom_up() {
+        let mut tree = ChristmasTreeFrame::default()
+            .with_ornament_density(1.0)
+            .unwrap()
+            .with_lights_charge(Duration::from_secs(2))
+            .unwrap();
+        tree.update(40, 20);
+        let mut scene = Scene::default();
+        tree.tick(&scene);
+        // the ornaments only light the scene for the snow to reflect them
+        assert!(tree.light_sources().is_empty());
+        tree = tree.with_reflections(true);
+        // only the first ornament on the bottom row is on to begin with
+        let lights = tree.light_sources();
+        assert_eq!(lights.len(), 1);
+        let bottom_y = lights[0].y;
+
+        let mut top_lit = Vec::new();
+        for seconds in [1, 2, 3] {
+            scene.time = Duration::from_secs(seconds);
+            tree.tick(&scene);
+            let lights = tree.light_sources();
+            top_lit.push(lights.iter().map(|light| light.y).min().unwrap());
+        }
+        // halfway through the wave has come to the first ornament of the
+        // middle row, then to all of them
+        assert_eq!(
+            top_lit,
+            [
+                bottom_y - LEAF_HEIGHT / 2,
+                bottom_y + 1 - LEAF_HEIGHT,
+                bottom_y + 1 - LEAF_HEIGHT
+            ]
+        );
+        assert_eq!(
+            tree.light_sources().len(),
+            tree.ornaments.iter().map(Vec::len).sum::<usize>()
+        );
+    }
+
+    #[test]
+    fn holds_the_ornaments_still_without_twinkling() {
+        let leaves = |tree: &mut ChristmasTreeFrame| {
+            tree.update(20, 14);
+            return (0..LEAF_HEIGHT)
+                .flat_map(|y| (0..20).map(move |x| (x, y)))
+                .map(|(x, y)| match tree.get_content(x, y) {
+                    Content::ColoredString { s } => s.to_string(),
+                    _ => String::new(),
+                })
+                .collect::<Vec<_>>();
+        };
+        let mut tree = ChristmasTreeFrame::default()
+            .with_seed(20241224)
+            .with_twinkle(false);
+        let first = leaves(&mut tree);
+        assert_eq!(leaves(&mut tree), first);
+    }
+
+    #[test]
+    fn rotates_blessings_by_the_clock() {
+        let mut tree = ChristmasTreeFrame::default()
+            .with_blessing_templates(&["One {year}".to_owned(), "Two".to_owned()])
+            .with_year(2030)
+            .with_blessing_interval(Duration::from_secs(2))
+            .unwrap();
+        let mut scene = Scene::default();
+        for (seconds, blessing) in [(0, "One 2030"), (3, "Two"), (4, "One 2030")] {
+            scene.time = Duration::from_secs(seconds);
+            tree.tick(&scene);
+            assert_eq!(tree.blessing, blessing);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use std::ops::Range;
+
+use colored::{Color, Colorize};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    color,
+    frame::{Bounds, Content, Frame},
+    scene::Scene,
+};
+
+const CLOUD_COUNT: usize = 3;
+// columns across the bottom of a cloud, its top being a column in on each side
+const MIN_CLOUD_WIDTH: usize = 8;
+const MAX_CLOUD_WIDTH: usize = 16;
+const CLOUD_HEIGHT: usize = 2;
+// columns the clouds drift right by per update on a still night, and the
+// fraction of each column of wind that carries them along
+const DRIFT: f32 = 0.1;
+const WIND_CARRY: f32 = 0.25;
+const CLOUD_BRIGHTNESS: f32 = 0.75;
+
+/// A few grey clouds drifting across the top of the sky with the wind,
+/// wrapping around the edges. They tell the scene the columns they hang
+/// over, for snow to fall from under them.
+pub struct CloudsFrame {
+    rng: StdRng,
+    frame_width: usize,
+    // (left column as a fraction of the screen width, width) of each cloud
+    clouds: Vec<(f32, usize)>,
+    // columns drifted by so far
+    drift: f32,
+}
+
+impl CloudsFrame {
+    /// Makes the clouds reproducible instead of seeding them from entropy.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.gather();
+        return self;
+    }
+
+    fn gather(&mut self) {
+        // spread out over the sky, one to each part of it
+        self.clouds = (0..CLOUD_COUNT)
+            .map(|cloud| {
+                let left = (cloud as f32 + self.rng.gen_range(0.0..0.5)) / CLOUD_COUNT as f32;
+                return (left, self.rng.gen_range(MIN_CLOUD_WIDTH..=MAX_CLOUD_WIDTH));
+            })
+            .collect();
+    }
+
+    // left column of each cloud on the screen and its width
+    fn placed(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let width = self.frame_width.max(1) as f32;
+        return self.clouds.iter().map(move |(left, cloud_width)| {
+            let x = (left * width + self.drift).rem_euclid(width) as usize;
+            return (x, *cloud_width);
+        });
+    }
+}
+
+impl Default for CloudsFrame {
+    fn default() -> Self {
+        let mut clouds_frame = CloudsFrame {
+            rng: StdRng::from_entropy(),
+            frame_width: 0,
+            clouds: Vec::new(),
+            drift: 0.0,
+        };
+        clouds_frame.gather();
+        return clouds_frame;
+    }
+}
+
+impl Frame for CloudsFrame {
+    fn update(&mut self, screen_width: usize, _screen_height: usize) {
+        self.frame_width = screen_width;
+    }
+
+    fn tick(&mut self, scene: &Scene) {
+        self.drift += DRIFT + WIND_CARRY * scene.wind as f32;
+    }
+
+    fn clouds(&self) -> Vec<Range<usize>> {
+        let mut columns = Vec::new();
+        for (x, width) in self.placed() {
+            // split the clouds that wrap around the right edge
+            let end = (x + width).min(self.frame_width);
+            columns.push(x..end);
+            if x + width > self.frame_width {
+                columns.push(0..x + width - self.frame_width);
+            }
+        }
+
+        return columns;
+    }
+
+    fn content_bounds(&self) -> Option<Bounds> {
+        return Some(Bounds {
+            x: 0,
+            y: 0,
+            width: self.frame_width,
+            height: CLOUD_HEIGHT,
+        });
+    }
+
+    fn get_content(&mut self, x: usize, y: usize) -> Content {
+        let over = self.placed().find_map(|(left, width)| {
+            let column = (x + self.frame_width - left) % self.frame_width.max(1);
+            return (column < width).then_some((column, width));
+        });
+        let Some((column, width)) = over else {
+            return Content::Transparent;
+        };
+
+        let s = match y {
+            0 if column > 0 && column + 1 < width => "▄",
+            1 => "█",
+            _ => return Content::Transparent,
+        };
+        return Content::ColoredString {
+            s: s.color(color::scale(Color::White, CLOUD_BRIGHTNESS)),
+        };
+    }
+}
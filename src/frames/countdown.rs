@@ -0,0 +1,137 @@
+use std::time::Duration;
+
+use colored::Colorize;
+
+use crate::{
+    frame::{Bounds, Content, Frame},
+    scene::{Event, Scene},
+};
+
+// rows of every glyph of the font, '#' being lit
+const GLYPH_HEIGHT: usize = 5;
+const DIGITS: [[&str; GLYPH_HEIGHT]; 10] = [
+    ["###", "# #", "# #", "# #", "###"],
+    [" # ", "## ", " # ", " # ", "###"],
+    ["###", "  #", "###", "#  ", "###"],
+    ["###", "  #", "###", "  #", "###"],
+    ["# #", "# #", "###", "  #", "  #"],
+    ["###", "#  ", "###", "  #", "###"],
+    ["###", "#  ", "###", "# #", "###"],
+    ["###", "  #", "  #", "  #", "  #"],
+    ["###", "# #", "###", "# #", "###"],
+    ["###", "# #", "###", "  #", "###"],
+];
+const COLON: [&str; GLYPH_HEIGHT] = [" ", "#", " ", "#", " "];
+
+/// Counts down in large digits to midnight, `until` into the scene's clock,
+/// then reports `Event::Midnight` and steps aside.
+pub struct CountdownFrame {
+    until: Duration,
+    frame_width: usize,
+    frame_height: usize,
+    // rows of the rendered countdown, each the same width
+    rows: Vec<String>,
+    // whole seconds still to go, as of the last tick
+    remaining: Option<u64>,
+    done: bool,
+}
+
+impl CountdownFrame {
+    pub fn new(until: Duration) -> Self {
+        return CountdownFrame {
+            until,
+            frame_width: 0,
+            frame_height: 0,
+            rows: Vec::new(),
+            remaining: None,
+            done: false,
+        };
+    }
+
+    fn render(seconds: u64) -> Vec<String> {
+        let text = match seconds {
+            0..=59 => seconds.to_string(),
+            60..=3_599 => format!("{}:{:02}", seconds / 60, seconds % 60),
+            _ => format!(
+                "{}:{:02}:{:02}",
+                seconds / 3_600,
+                seconds / 60 % 60,
+                seconds % 60
+            ),
+        };
+
+        return (0..GLYPH_HEIGHT)
+            .map(|row| {
+                return text
+                    .chars()
+                    .map(|c| match c.to_digit(10) {
+                        Some(digit) => DIGITS[digit as usize][row],
+                        None => COLON[row],
+                    })
+                    .collect::<Vec<&str>>()
+                    .join(" ");
+            })
+            .collect();
+    }
+}
+
+impl Frame for CountdownFrame {
+    fn update(&mut self, screen_width: usize, screen_height: usize) {
+        self.frame_width = screen_width;
+        self.frame_height = screen_height;
+    }
+
+    fn events(&self) -> Vec<Event> {
+        if self.remaining == Some(0) && !self.done {
+            return vec![Event::Midnight];
+        }
+
+        return Vec::new();
+    }
+
+    fn tick(&mut self, scene: &Scene) {
+        if scene.events().contains(&Event::Midnight) {
+            self.done = true;
+        }
+
+        // round up so the count shows its starting number and hits zero on time
+        let left = self.until.saturating_sub(scene.time);
+        let seconds = left.as_secs() + u64::from(left.subsec_nanos() > 0);
+        if self.remaining != Some(seconds) {
+            self.remaining = Some(seconds);
+            self.rows = CountdownFrame::render(seconds);
+        }
+    }
+
+    fn content_bounds(&self) -> Option<Bounds> {
+        if self.done {
+            return Some(Bounds::EMPTY);
+        }
+
+        let width = self.rows.first().map_or(0, |row| row.len());
+        return Some(Bounds {
+            x: self.frame_width.saturating_sub(width) / 2,
+            y: self.frame_height.saturating_sub(GLYPH_HEIGHT) / 2,
+            width,
+            height: self.rows.len(),
+        });
+    }
+
+    fn get_content(&mut self, x: usize, y: usize) -> Content {
+        if self.done {
+            return Content::Transparent;
+        }
+
+        let y_offset = self.frame_height.saturating_sub(GLYPH_HEIGHT) / 2;
+        let Some(row) = y.checked_sub(y_offset).and_then(|row| self.rows.get(row)) else {
+            return Content::Transparent;
+        };
+        let x_offset = self.frame_width.saturating_sub(row.len()) / 2;
+        return match x.checked_sub(x_offset).and_then(|x| row.as_bytes().get(x)) {
+            Some(b'#') => Content::ColoredString {
+                s: "#".bright_yellow(),
+            },
+            _ => Content::Transparent,
+        };
+    }
+}
@@ -0,0 +1,54 @@
+use colored::Colorize;
+
+use crate::{
+    frame::{Content, Frame},
+    scene::Scene,
+};
+
+/// A readout of the scene's internals for tuning the snow, meant as the top
+/// layer: the wind in the top left corner and the depth of the ground snow
+/// of every column along the bottom row, `+` standing for ten or more.
+///
+/// The depths are the ones reported on this update, before the snow falling
+/// on it has landed.
+#[derive(Default)]
+pub struct DebugFrame {
+    frame_width: usize,
+    frame_height: usize,
+    wind: String,
+    depths: String,
+}
+
+impl Frame for DebugFrame {
+    fn update(&mut self, screen_width: usize, screen_height: usize) {
+        self.frame_width = screen_width;
+        self.frame_height = screen_height;
+    }
+
+    fn tick(&mut self, scene: &Scene) {
+        self.wind = format!("wind {:+}", scene.wind);
+        self.depths = (0..self.frame_width)
+            .map(|x| match scene.snow_depth(x) {
+                depth @ 0..=9 => char::from_digit(depth as u32, 10).unwrap(),
+                _ => '+',
+            })
+            .collect();
+    }
+
+    fn get_content(&mut self, x: usize, y: usize) -> Content {
+        let glyph = if y + 1 == self.frame_height {
+            self.depths.chars().nth(x)
+        } else if y == 0 {
+            self.wind.chars().nth(x)
+        } else {
+            None
+        };
+
+        return match glyph {
+            Some(glyph) => Content::ColoredString {
+                s: glyph.to_string().black().on_bright_yellow(),
+            },
+            None => Content::Transparent,
+        };
+    }
+}
@@ -0,0 +1,191 @@
+use std::f32::consts::TAU;
+
+use colored::{Color, Colorize};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    frame::{Content, Frame},
+    scene::{Event, Scene},
+};
+
+// rows a rocket climbs per update
+const ROCKET_SPEED: f32 = 1.5;
+// chance of a rocket taking off on each update
+const LAUNCH_CHANCE: f64 = 0.4;
+const SPARKS_PER_BURST: usize = 12;
+// columns a spark flies per update as it bursts out
+const SPARK_SPEED: f32 = 1.5;
+// rows per update a spark's fall speeds up by
+const GRAVITY: f32 = 0.15;
+// updates a spark glows for, the last half of them fading
+const SPARK_LIFETIME: usize = 6;
+const COLORS: [Color; 6] = [
+    Color::BrightRed,
+    Color::BrightYellow,
+    Color::BrightGreen,
+    Color::BrightCyan,
+    Color::BrightMagenta,
+    Color::BrightWhite,
+];
+
+struct Rocket {
+    x: usize,
+    y: f32,
+    burst_y: f32,
+    color: Color,
+}
+
+struct Spark {
+    x: f32,
+    y: f32,
+    vx: f32,
+    vy: f32,
+    age: usize,
+    color: Color,
+}
+
+/// Rockets shooting up from the ground and bursting into sparks, from the
+/// first `Event::Midnight` onwards.
+pub struct FireworksFrame {
+    rng: StdRng,
+    frame_width: usize,
+    frame_height: usize,
+    erupting: bool,
+    rockets: Vec<Rocket>,
+    sparks: Vec<Spark>,
+    // what is drawn at each cell, row by row
+    cells: Vec<Option<(char, Color)>>,
+}
+
+impl FireworksFrame {
+    /// Makes the fireworks reproducible instead of seeding them from entropy.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        return self;
+    }
+
+    fn launch(&mut self) {
+        if self.frame_width == 0 || self.frame_height == 0 {
+            return;
+        }
+
+        let x = self
+            .rng
+            .gen_range(self.frame_width / 8..=self.frame_width * 7 / 8);
+        let burst_y = self
+            .rng
+            .gen_range(self.frame_height as f32 / 8.0..=self.frame_height as f32 / 2.0);
+        let color = COLORS[self.rng.gen_range(0..COLORS.len())];
+        self.rockets.push(Rocket {
+            x,
+            y: self.frame_height as f32,
+            burst_y,
+            color,
+        });
+    }
+
+    fn step(&mut self) {
+        for spark in self.sparks.iter_mut() {
+            spark.x += spark.vx;
+            spark.y += spark.vy;
+            spark.vy += GRAVITY;
+            spark.age += 1;
+        }
+        self.sparks.retain(|spark| spark.age < SPARK_LIFETIME);
+
+        let mut bursts = Vec::new();
+        for rocket in self.rockets.iter_mut() {
+            rocket.y -= ROCKET_SPEED;
+            if rocket.y <= rocket.burst_y {
+                bursts.push((rocket.x as f32, rocket.burst_y, rocket.color));
+            }
+        }
+        self.rockets.retain(|rocket| rocket.y > rocket.burst_y);
+        for (x, y, color) in bursts {
+            for i in 0..SPARKS_PER_BURST {
+                let angle = TAU * i as f32 / SPARKS_PER_BURST as f32;
+                // rows are about twice as tall as columns are wide
+                self.sparks.push(Spark {
+                    x,
+                    y,
+                    vx: angle.cos() * SPARK_SPEED,
+                    vy: angle.sin() * SPARK_SPEED / 2.0,
+                    age: 0,
+                    color,
+                });
+            }
+        }
+
+        if self.rng.gen_bool(LAUNCH_CHANCE) {
+            self.launch();
+        }
+    }
+
+    fn draw(&mut self) {
+        self.cells = vec![None; self.frame_width * self.frame_height];
+        let cells = &mut self.cells;
+        let (width, height) = (self.frame_width, self.frame_height);
+        let mut plot = |x: f32, y: f32, glyph: char, color: Color| {
+            let (x, y) = (x.round(), y.round());
+            if x >= 0.0 && y >= 0.0 && (x as usize) < width && (y as usize) < height {
+                cells[y as usize * width + x as usize] = Some((glyph, color));
+            }
+        };
+
+        for rocket in self.rockets.iter() {
+            plot(rocket.x as f32, rocket.y, '|', rocket.color);
+        }
+        for spark in self.sparks.iter() {
+            let glyph = if spark.age < SPARK_LIFETIME / 2 {
+                '*'
+            } else {
+                '.'
+            };
+            plot(spark.x, spark.y, glyph, spark.color);
+        }
+    }
+}
+
+impl Default for FireworksFrame {
+    fn default() -> Self {
+        return FireworksFrame {
+            rng: StdRng::from_entropy(),
+            frame_width: 0,
+            frame_height: 0,
+            erupting: false,
+            rockets: Vec::new(),
+            sparks: Vec::new(),
+            cells: Vec::new(),
+        };
+    }
+}
+
+impl Frame for FireworksFrame {
+    fn update(&mut self, screen_width: usize, screen_height: usize) {
+        if self.frame_width != screen_width || self.frame_height != screen_height {
+            self.frame_width = screen_width;
+            self.frame_height = screen_height;
+            self.rockets.clear();
+            self.sparks.clear();
+        }
+    }
+
+    fn tick(&mut self, scene: &Scene) {
+        if scene.events().contains(&Event::Midnight) {
+            self.erupting = true;
+        }
+        if self.erupting {
+            self.step();
+        }
+        self.draw();
+    }
+
+    fn get_content(&mut self, x: usize, y: usize) -> Content {
+        return match self.cells.get(y * self.frame_width + x) {
+            Some(Some((glyph, color))) => Content::ColoredString {
+                s: glyph.to_string().color(*color),
+            },
+            _ => Content::Transparent,
+        };
+    }
+}
@@ -0,0 +1,101 @@
+use colored::{Color, Colorize};
+
+use crate::{
+    frame::{Content, Frame},
+    scene::{Light, Scene},
+};
+
+// columns between two hooks the garland hangs from
+const SPAN: usize = 12;
+// rows the garland sags by between two hooks
+const SAG: f32 = 2.0;
+// columns between two bulbs
+const BULB_SPACING: usize = 3;
+// how far the glow of a bulb reaches, in columns
+const BULB_GLOW_RADIUS: f32 = 2.0;
+const BULB_COLORS: [Color; 4] = [Color::Red, Color::Yellow, Color::Blue, Color::Magenta];
+
+/// A garland of blinking bulbs strung across the top of the world. It spans
+/// the scene's whole virtual width, so a panning camera carries it by. The
+/// colors chase along it once per update, or once per beat given a tempo.
+#[derive(Default)]
+pub struct GarlandFrame {
+    frame_width: usize,
+    world_width: usize,
+    camera_x: usize,
+    phase: usize,
+}
+
+impl GarlandFrame {
+    // column of the world shown at column `x`
+    fn world_x(&self, x: usize) -> usize {
+        return (self.camera_x + x) % self.world_width;
+    }
+
+    // row the garland hangs at in column `world_x` of the world
+    fn sag_at(&self, world_x: usize) -> usize {
+        let t = (world_x % SPAN) as f32 / SPAN as f32;
+        return (SAG * (t * std::f32::consts::PI).sin()).round() as usize;
+    }
+
+    fn bulb_at(&self, world_x: usize) -> Option<Color> {
+        if world_x.is_multiple_of(SPAN) || world_x % BULB_SPACING != BULB_SPACING / 2 {
+            return None;
+        }
+
+        return Some(BULB_COLORS[(world_x / BULB_SPACING + self.phase) % BULB_COLORS.len()]);
+    }
+}
+
+impl Frame for GarlandFrame {
+    fn update(&mut self, screen_width: usize, _screen_height: usize) {
+        self.frame_width = screen_width;
+        self.phase = self.phase.wrapping_add(1);
+    }
+
+    fn light_sources(&self) -> Vec<Light> {
+        if self.world_width == 0 {
+            return Vec::new();
+        }
+
+        return (0..self.frame_width)
+            .filter_map(|x| {
+                let world_x = self.world_x(x);
+                return self.bulb_at(world_x).map(|color| Light {
+                    x,
+                    y: self.sag_at(world_x),
+                    radius: BULB_GLOW_RADIUS,
+                    color,
+                });
+            })
+            .collect();
+    }
+
+    fn tick(&mut self, scene: &Scene) {
+        self.world_width = scene.world_width();
+        self.camera_x = scene.camera_x();
+        if let Some(beat) = scene.beat() {
+            self.phase = beat as usize;
+        }
+    }
+
+    fn get_content(&mut self, x: usize, y: usize) -> Content {
+        if self.world_width == 0 {
+            return Content::Transparent;
+        }
+
+        let world_x = self.world_x(x);
+        if y != self.sag_at(world_x) {
+            return Content::Transparent;
+        }
+
+        let s = if world_x.is_multiple_of(SPAN) {
+            "+".bright_white()
+        } else if let Some(color) = self.bulb_at(world_x) {
+            "o".color(color)
+        } else {
+            "~".green()
+        };
+        return Content::ColoredString { s };
+    }
+}
@@ -0,0 +1,158 @@
+use colored::{Color, Colorize};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    color,
+    frame::{Bounds, Content, Frame},
+    scene::Surface,
+};
+
+const GIFT_COUNT: usize = 3;
+const GIFT_WIDTH: usize = 5;
+const GIFT_GAP: usize = 1;
+// a bow on top of a lid and a box under it
+const GIFT_HEIGHT: usize = 3;
+// ribbons to choose from, whichever stands out the most against the box
+const RIBBONS: [Color; 4] = [
+    Color::BrightYellow,
+    Color::BrightWhite,
+    Color::Red,
+    Color::Black,
+];
+
+/// A few wrapped presents on the ground under the tree, each box of a random
+/// color tied with a ribbon that stands out against it.
+pub struct GiftsFrame {
+    rng: StdRng,
+    frame_width: usize,
+    frame_height: usize,
+    // (box, ribbon) colors of each gift, left to right
+    gifts: Vec<(Color, Color)>,
+}
+
+impl GiftsFrame {
+    /// Makes the colors of the boxes reproducible instead of seeding them
+    /// from entropy.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.wrap();
+        return self;
+    }
+
+    fn wrap(&mut self) {
+        self.gifts = (0..GIFT_COUNT)
+            .map(|_| {
+                let box_color = Color::TrueColor {
+                    r: self.rng.gen(),
+                    g: self.rng.gen(),
+                    b: self.rng.gen(),
+                };
+                return (box_color, color::contrasting(box_color, &RIBBONS));
+            })
+            .collect();
+    }
+
+    fn bounds(&self) -> Bounds {
+        let width = GIFT_COUNT * GIFT_WIDTH + (GIFT_COUNT - 1) * GIFT_GAP;
+        return Bounds {
+            x: self.frame_width.saturating_sub(width) / 2,
+            y: self.frame_height.saturating_sub(GIFT_HEIGHT),
+            width,
+            height: GIFT_HEIGHT.min(self.frame_height),
+        };
+    }
+
+    // the gift at column `x` and the column of it, if there is one
+    fn gift_at(&self, x: usize) -> Option<(usize, usize)> {
+        let x = x.checked_sub(self.bounds().x)?;
+        let (gift, column) = (x / (GIFT_WIDTH + GIFT_GAP), x % (GIFT_WIDTH + GIFT_GAP));
+        if gift >= GIFT_COUNT || column >= GIFT_WIDTH {
+            return None;
+        }
+
+        return Some((gift, column));
+    }
+}
+
+impl Default for GiftsFrame {
+    fn default() -> Self {
+        let mut gifts_frame = GiftsFrame {
+            rng: StdRng::from_entropy(),
+            frame_width: 0,
+            frame_height: 0,
+            gifts: Vec::new(),
+        };
+        gifts_frame.wrap();
+        return gifts_frame;
+    }
+}
+
+impl Frame for GiftsFrame {
+    fn update(&mut self, screen_width: usize, screen_height: usize) {
+        self.frame_width = screen_width;
+        self.frame_height = screen_height;
+    }
+
+    fn is_solid(&self, x: usize, y: usize) -> bool {
+        // the bow is too small to hold up any snow
+        return y + 2 >= self.frame_height
+            && self.bounds().contains(x, y)
+            && self.gift_at(x).is_some();
+    }
+
+    fn solid_surfaces(&self) -> Vec<Surface> {
+        let Some(lid_y) = self.frame_height.checked_sub(2) else {
+            return Vec::new();
+        };
+
+        return (0..GIFT_COUNT)
+            .map(|gift| Surface {
+                x: self.bounds().x + gift * (GIFT_WIDTH + GIFT_GAP),
+                y: lid_y,
+                width: GIFT_WIDTH,
+            })
+            .collect();
+    }
+
+    fn content_bounds(&self) -> Option<Bounds> {
+        return Some(self.bounds());
+    }
+
+    fn get_content(&mut self, x: usize, y: usize) -> Content {
+        let Some((gift, column)) = self.gift_at(x) else {
+            return Content::Transparent;
+        };
+        let (box_color, ribbon) = self.gifts[gift];
+        let knot = column == GIFT_WIDTH / 2;
+
+        let s = match self.frame_height.saturating_sub(y) {
+            1 if knot => "┃".color(ribbon).on_color(box_color),
+            1 => " ".on_color(box_color),
+            2 if knot => "╋".color(ribbon).on_color(box_color),
+            2 => "━".color(ribbon).on_color(box_color),
+            3 if knot => "∞".color(ribbon),
+            _ => return Content::Transparent,
+        };
+        return Content::ColoredString { s };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ties_each_box_with_a_ribbon_that_stands_out() {
+        for seed in 0..20 {
+            let gifts_frame = GiftsFrame::default().with_seed(seed);
+            for (box_color, ribbon) in gifts_frame.gifts {
+                assert!(
+                    color::contrast(box_color, ribbon) >= 3.0,
+                    "{:?} ribbon on a {:?} box",
+                    ribbon,
+                    box_color
+                );
+            }
+        }
+    }
+}
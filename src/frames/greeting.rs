@@ -0,0 +1,147 @@
+use colored::Color;
+
+use crate::{
+    frame::{string_to_content_vec, Bounds, Content, Frame},
+    string_width::{char_width, StringWidth},
+};
+
+// columns a name is cut down to, ellipsis included
+const MAX_NAME_WIDTH: usize = 20;
+const ELLIPSIS: char = '…';
+
+/// A line addressing the card, like "To Alice, from Bob", centred near the
+/// bottom of the screen. Shows nothing until given a name.
+#[derive(Default)]
+pub struct GreetingFrame {
+    to: Option<String>,
+    from: Option<String>,
+    frame_width: usize,
+    frame_height: usize,
+    line: String,
+    contents: Vec<Content>,
+}
+
+impl GreetingFrame {
+    /// Addresses the card to `to`, cut short with an ellipsis if too long.
+    pub fn with_to(mut self, to: &str) -> Self {
+        self.to = Some(truncate(to, MAX_NAME_WIDTH));
+        return self;
+    }
+
+    /// Signs the card from `from`, cut short with an ellipsis if too long.
+    pub fn with_from(mut self, from: &str) -> Self {
+        self.from = Some(truncate(from, MAX_NAME_WIDTH));
+        return self;
+    }
+
+    fn greeting(&self) -> String {
+        return match (&self.to, &self.from) {
+            (Some(to), Some(from)) => format!("To {}, from {}", to, from),
+            (Some(to), None) => format!("To {}", to),
+            (None, Some(from)) => format!("From {}", from),
+            (None, None) => String::new(),
+        };
+    }
+
+    // the row above the bottom one, which is left to the ground snow
+    fn row(&self) -> usize {
+        return self.frame_height.saturating_sub(2);
+    }
+
+    fn left(&self) -> usize {
+        return self.frame_width.saturating_sub(self.line.width()) / 2;
+    }
+}
+
+impl Frame for GreetingFrame {
+    fn update(&mut self, screen_width: usize, screen_height: usize) {
+        if self.frame_width == screen_width && self.frame_height == screen_height {
+            return;
+        }
+
+        self.frame_width = screen_width;
+        self.frame_height = screen_height;
+        self.line = truncate(&self.greeting(), screen_width);
+        self.contents = string_to_content_vec(&self.line, Color::Yellow);
+    }
+
+    fn content_bounds(&self) -> Option<Bounds> {
+        return Some(Bounds {
+            x: self.left(),
+            y: self.row(),
+            width: self.contents.len(),
+            height: 1,
+        });
+    }
+
+    fn get_content(&mut self, x: usize, y: usize) -> Content {
+        if y != self.row() {
+            return Content::Transparent;
+        }
+
+        return match x
+            .checked_sub(self.left())
+            .and_then(|x| self.contents.get(x))
+        {
+            Some(content) => content.clone(),
+            None => Content::Transparent,
+        };
+    }
+}
+
+// cuts `s` down to `width` columns, ending it with an ellipsis if anything
+// had to go
+fn truncate(s: &str, width: usize) -> String {
+    if s.width() <= width {
+        return s.to_owned();
+    }
+    let Some(room) = width.checked_sub(char_width(ELLIPSIS)) else {
+        return String::new();
+    };
+
+    let mut truncated = String::new();
+    for c in s.chars() {
+        // a wide character that does not fit whole is left out
+        if truncated.width() + char_width(c) > room {
+            break;
+        }
+        truncated.push(c);
+    }
+    truncated.push(ELLIPSIS);
+    return truncated;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shown(greeting: &mut GreetingFrame, width: usize) -> String {
+        greeting.update(width, 5);
+        return (0..width)
+            .map(|x| match greeting.get_content(x, 3) {
+                Content::ColoredString { s } => s.input,
+                _ => " ".to_owned(),
+            })
+            .collect();
+    }
+
+    #[test]
+    fn addresses_the_card_cutting_long_names_short() {
+        assert_eq!(shown(&mut GreetingFrame::default(), 10), " ".repeat(10));
+        assert_eq!(
+            shown(&mut GreetingFrame::default().with_from("Bob"), 12),
+            "  From Bob  "
+        );
+
+        let mut greeting = GreetingFrame::default()
+            .with_to("Alice")
+            .with_from("Bartholomew Fitzgerald");
+        assert_eq!(
+            shown(&mut greeting, 40),
+            "  To Alice, from Bartholomew Fitzger…   "
+        );
+        // and the whole line to fit the screen
+        assert_eq!(shown(&mut greeting, 12), "To Alice, f…");
+        assert_eq!(truncate("聖誕快樂", 6), "聖誕…");
+    }
+}
@@ -0,0 +1,219 @@
+use std::ops::Range;
+
+use crate::{
+    frame::{Bounds, Content, Frame},
+    scene::{Event, Ledge, Light, Scene, Surface},
+    string_width::StringWidth,
+};
+
+/// Pairs of glyphs facing opposite ways, for mirroring frames drawn in ASCII
+/// and box-drawing characters.
+pub const MIRRORED_GLYPHS: [(char, char); 10] = [
+    ('(', ')'),
+    ('[', ']'),
+    ('{', '}'),
+    ('<', '>'),
+    ('/', '\\'),
+    ('┌', '┐'),
+    ('└', '┘'),
+    ('├', '┤'),
+    ('╭', '╮'),
+    ('╰', '╯'),
+];
+
+/// Flips another frame left to right, so a sprite facing one way can be
+/// reused facing the other. Glyphs facing a way are only swapped for the
+/// ones facing the other way if given with `with_glyph_pairs`. Wide glyphs
+/// stay whole, still drawn from their left column.
+pub struct MirrorFrame {
+    inner: Box<dyn Frame>,
+    frame_width: usize,
+    glyph_pairs: Vec<(char, char)>,
+}
+
+impl MirrorFrame {
+    pub fn new(inner: Box<dyn Frame>) -> Self {
+        return MirrorFrame {
+            inner,
+            frame_width: 0,
+            glyph_pairs: Vec::new(),
+        };
+    }
+
+    /// Swaps each glyph of a pair for the other one wherever the inner frame
+    /// shows it, e.g. [`MIRRORED_GLYPHS`].
+    pub fn with_glyph_pairs(mut self, glyph_pairs: &[(char, char)]) -> Self {
+        self.glyph_pairs = glyph_pairs.to_vec();
+        return self;
+    }
+
+    // column of the inner frame shown at column `x`, and the other way round
+    fn mirror_x(&self, x: usize) -> usize {
+        return self.frame_width.saturating_sub(x + 1);
+    }
+
+    // left column of a run of `width` columns from `x` once mirrored
+    fn mirror_run(&self, x: usize, width: usize) -> usize {
+        return self.frame_width.saturating_sub(x + width);
+    }
+
+    fn mirror_glyph(&self, c: char) -> char {
+        for &(left, right) in &self.glyph_pairs {
+            if c == left {
+                return right;
+            } else if c == right {
+                return left;
+            }
+        }
+
+        return c;
+    }
+}
+
+impl Frame for MirrorFrame {
+    fn update(&mut self, screen_width: usize, screen_height: usize) {
+        self.frame_width = screen_width;
+        self.inner.update(screen_width, screen_height);
+    }
+
+    fn is_solid(&self, x: usize, y: usize) -> bool {
+        return self.inner.is_solid(self.mirror_x(x), y);
+    }
+
+    fn solid_surfaces(&self) -> Vec<Surface> {
+        return self
+            .inner
+            .solid_surfaces()
+            .into_iter()
+            .map(|surface| Surface {
+                x: self.mirror_run(surface.x, surface.width),
+                ..surface
+            })
+            .collect();
+    }
+
+    fn snow_ledges(&self) -> Vec<Ledge> {
+        return self
+            .inner
+            .snow_ledges()
+            .into_iter()
+            .map(|ledge| Ledge {
+                surface: Surface {
+                    x: self.mirror_run(ledge.surface.x, ledge.surface.width),
+                    ..ledge.surface
+                },
+                ..ledge
+            })
+            .collect();
+    }
+
+    fn clouds(&self) -> Vec<Range<usize>> {
+        return self
+            .inner
+            .clouds()
+            .into_iter()
+            .map(|columns| {
+                let start = self.mirror_run(columns.start, columns.len());
+                return start..start + columns.len();
+            })
+            .collect();
+    }
+
+    fn light_sources(&self) -> Vec<Light> {
+        return self
+            .inner
+            .light_sources()
+            .into_iter()
+            .map(|light| Light {
+                x: self.mirror_x(light.x),
+                ..light
+            })
+            .collect();
+    }
+
+    fn snow_depths(&self) -> Vec<usize> {
+        let mut depths = self.inner.snow_depths();
+        depths.reverse();
+        return depths;
+    }
+
+    fn content_bounds(&self) -> Option<Bounds> {
+        let bounds = self.inner.content_bounds()?;
+        return Some(Bounds {
+            x: self.mirror_run(bounds.x, bounds.width),
+            ..bounds
+        });
+    }
+
+    fn events(&self) -> Vec<Event> {
+        return self.inner.events();
+    }
+
+    fn tick(&mut self, scene: &Scene) {
+        self.inner.tick(scene);
+    }
+
+    fn get_content(&mut self, x: usize, y: usize) -> Content {
+        let inner_x = self.mirror_x(x);
+        // a wide glyph mirrored starts where its second column ends up, and
+        // its first column is left to compensate for it
+        let mut s = match self.inner.get_content(inner_x, y) {
+            Content::Compensate => match inner_x.checked_sub(1) {
+                Some(glyph_x) => match self.inner.get_content(glyph_x, y) {
+                    Content::ColoredString { s } => s,
+                    _ => return Content::Transparent,
+                },
+                None => return Content::Transparent,
+            },
+            Content::ColoredString { s } if s.input.width() > 1 => return Content::Compensate,
+            Content::ColoredString { s } => s,
+            Content::Transparent => return Content::Transparent,
+        };
+
+        s.input = s.input.chars().map(|c| self.mirror_glyph(c)).collect();
+        return Content::ColoredString { s };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use colored::Colorize;
+
+    use super::*;
+
+    struct RowFrame(Vec<Content>);
+
+    impl Frame for RowFrame {
+        fn update(&mut self, _screen_width: usize, _screen_height: usize) {}
+
+        fn get_content(&mut self, x: usize, _y: usize) -> Content {
+            return self.0.get(x).cloned().unwrap_or(Content::Transparent);
+        }
+    }
+
+    fn glyph(s: &str) -> Content {
+        return Content::ColoredString { s: s.normal() };
+    }
+
+    #[test]
+    fn keeps_wide_glyphs_whole_and_turns_the_given_glyphs_round() {
+        let row = RowFrame(vec![
+            glyph("<"),
+            glyph("雪"),
+            Content::Compensate,
+            Content::Transparent,
+            glyph("x"),
+        ]);
+        let mut mirror = MirrorFrame::new(Box::new(row)).with_glyph_pairs(&MIRRORED_GLYPHS);
+        mirror.update(5, 1);
+
+        let shown: Vec<String> = (0..5)
+            .map(|x| match mirror.get_content(x, 0) {
+                Content::ColoredString { s } => s.input,
+                Content::Compensate => "+".to_owned(),
+                Content::Transparent => " ".to_owned(),
+            })
+            .collect();
+        assert_eq!(shown, ["x", " ", "雪", "+", ">"]);
+    }
+}
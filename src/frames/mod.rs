@@ -0,0 +1,35 @@
+mod aurora;
+mod banner;
+mod candles;
+mod christmas_tree;
+mod clouds;
+mod countdown;
+mod debug;
+mod fireworks;
+mod garland;
+mod gifts;
+mod greeting;
+mod mirror;
+mod parallax;
+mod santa_sleigh;
+mod snow;
+mod snowman;
+mod window;
+
+pub use aurora::AuroraFrame;
+pub use banner::BannerFrame;
+pub use candles::CandlesFrame;
+pub use christmas_tree::{BlessingAlign, ChristmasTreeFrame, TreeShape, DEFAULT_ORNAMENT_DENSITY};
+pub use clouds::CloudsFrame;
+pub use countdown::CountdownFrame;
+pub use debug::DebugFrame;
+pub use fireworks::FireworksFrame;
+pub use garland::GarlandFrame;
+pub use gifts::GiftsFrame;
+pub use greeting::GreetingFrame;
+pub use mirror::{MirrorFrame, MIRRORED_GLYPHS};
+pub use parallax::ParallaxFrame;
+pub use santa_sleigh::{SantaSleighFrame, DEFAULT_SLEIGH_INTERVAL};
+pub use snow::SnowFrame;
+pub use snowman::SnowmanFrame;
+pub use window::{WindowFrame, DEFAULT_WINDOW_LEDGE};
@@ -0,0 +1,174 @@
+use std::ops::Range;
+
+use crate::{
+    frame::{Content, Frame},
+    scene::{Event, Ledge, Light, Scene, Surface},
+};
+
+/// Scrolls another frame sideways at `parallax` times the scene's scroll,
+/// wrapping around the edges. Background layers scrolling slower than the
+/// foreground give the card some depth. A `parallax` of infinity or NaN is
+/// an error.
+pub struct ParallaxFrame {
+    inner: Box<dyn Frame>,
+    parallax: f32,
+    frame_width: usize,
+    // columns the inner frame is shifted left by, as of the last tick
+    offset: usize,
+}
+
+impl ParallaxFrame {
+    pub fn new(inner: Box<dyn Frame>, parallax: f32) -> Result<Self, String> {
+        if !parallax.is_finite() {
+            return Err(format!(
+                "parallax must be a finite number, got {}",
+                parallax
+            ));
+        }
+
+        return Ok(ParallaxFrame {
+            inner,
+            parallax,
+            frame_width: 0,
+            offset: 0,
+        });
+    }
+
+    // column of the inner frame shown at column `x`
+    fn inner_x(&self, x: usize) -> usize {
+        return (x + self.offset) % self.frame_width;
+    }
+
+    // column the inner frame's column `x` is shown at
+    fn outer_x(&self, x: usize) -> usize {
+        return (x + self.frame_width - self.offset % self.frame_width) % self.frame_width;
+    }
+
+    // where the inner frame's `surface` is shown, split in two if it wraps
+    // around the right edge
+    fn place(&self, surface: Surface) -> Vec<Surface> {
+        let x = self.outer_x(surface.x);
+        let width = surface.width.min(self.frame_width - x);
+        let mut placed = vec![Surface {
+            x,
+            width,
+            ..surface
+        }];
+        if width < surface.width {
+            placed.push(Surface {
+                x: 0,
+                width: surface.width - width,
+                ..surface
+            });
+        }
+
+        return placed;
+    }
+}
+
+impl Frame for ParallaxFrame {
+    fn update(&mut self, screen_width: usize, screen_height: usize) {
+        self.frame_width = screen_width;
+        self.inner.update(screen_width, screen_height);
+    }
+
+    // solids, surfaces and lights are gathered before the tick, so they trail
+    // the scroll by one update
+    fn is_solid(&self, x: usize, y: usize) -> bool {
+        return self.inner.is_solid(self.inner_x(x), y);
+    }
+
+    fn solid_surfaces(&self) -> Vec<Surface> {
+        return self
+            .inner
+            .solid_surfaces()
+            .into_iter()
+            .flat_map(|surface| self.place(surface))
+            .collect();
+    }
+
+    fn snow_ledges(&self) -> Vec<Ledge> {
+        return self
+            .inner
+            .snow_ledges()
+            .into_iter()
+            .flat_map(|ledge| {
+                return self
+                    .place(ledge.surface)
+                    .into_iter()
+                    .map(move |surface| Ledge { surface, ..ledge });
+            })
+            .collect();
+    }
+
+    fn clouds(&self) -> Vec<Range<usize>> {
+        return self
+            .inner
+            .clouds()
+            .into_iter()
+            .flat_map(|columns| {
+                let surface = Surface {
+                    x: columns.start,
+                    y: 0,
+                    width: columns.len(),
+                };
+                return self.place(surface);
+            })
+            .map(|surface| surface.x..surface.x + surface.width)
+            .collect();
+    }
+
+    fn light_sources(&self) -> Vec<Light> {
+        return self
+            .inner
+            .light_sources()
+            .into_iter()
+            .map(|light| Light {
+                x: self.outer_x(light.x),
+                ..light
+            })
+            .collect();
+    }
+
+    fn snow_depths(&self) -> Vec<usize> {
+        let depths = self.inner.snow_depths();
+        if depths.is_empty() {
+            return depths;
+        }
+
+        return (0..self.frame_width)
+            .map(|x| depths.get(self.inner_x(x)).copied().unwrap_or(0))
+            .collect();
+    }
+
+    fn events(&self) -> Vec<Event> {
+        return self.inner.events();
+    }
+
+    fn tick(&mut self, scene: &Scene) {
+        let offset = (scene.scroll * self.parallax).round() as isize;
+        self.offset = offset.rem_euclid(self.frame_width.max(1) as isize) as usize;
+        self.inner.tick(scene);
+    }
+
+    fn get_content(&mut self, x: usize, y: usize) -> Content {
+        let x = self.inner_x(x);
+        return self.inner.get_content(x, y);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frames::SnowFrame;
+
+    #[test]
+    fn rejects_infinite_parallax() {
+        let error = ParallaxFrame::new(Box::<SnowFrame>::default(), f32::INFINITY).err();
+        assert_eq!(
+            error.as_deref(),
+            Some("parallax must be a finite number, got inf")
+        );
+        assert!(ParallaxFrame::new(Box::<SnowFrame>::default(), -1.0).is_ok());
+    }
+}
@@ -0,0 +1,243 @@
+use colored::{Color, Colorize};
+
+use crate::frame::{Bounds, Content, Frame};
+
+/// Default number of updates between two flights.
+pub const DEFAULT_SLEIGH_INTERVAL: usize = 60;
+
+// row of the sky the convoy flies on, the bubble floating on the row above
+const CONVOY_Y: usize = 2;
+const COLUMNS_PER_UPDATE: isize = 3;
+const REINDEER_COUNT: usize = 3;
+const BUBBLE: &str = "Ho Ho Ho!";
+// column of the convoy santa sits on
+const SANTA_X: usize = 2;
+
+const REINDEER_BROWN: Color = Color::TrueColor {
+    r: 160,
+    g: 82,
+    b: 45,
+};
+
+/// Santa's sleigh pulled by a team of reindeer, flying left to right across
+/// the sky every so often and calling out "Ho Ho Ho!" halfway through.
+pub struct SantaSleighFrame {
+    frame_width: usize,
+    interval: usize,
+    bubble: bool,
+    convoy: Vec<Vec<Content>>,
+    convoy_width: usize,
+    // column of the convoy's left edge, none while waiting for the next flight
+    position: Option<isize>,
+    waiting: usize,
+}
+
+impl SantaSleighFrame {
+    /// Sets how many updates pass between the end of one flight and the next.
+    /// The first flight takes off straight away.
+    pub fn with_interval(mut self, interval: usize) -> Self {
+        self.interval = interval;
+        return self;
+    }
+
+    /// Whether santa calls out "Ho Ho Ho!" in a bubble over the middle of the
+    /// sky. On by default.
+    pub fn with_bubble(mut self, bubble: bool) -> Self {
+        self.bubble = bubble;
+        return self;
+    }
+
+    fn build_convoy() -> Vec<Vec<Content>> {
+        // (row 0, row 1, color) of each part, front of the convoy last
+        let mut parts = vec![(" _o_ ", "\\___/", Color::Red)];
+        for i in 0..REINDEER_COUNT {
+            parts.push((" ", "-", Color::White));
+            parts.push(("    Y", "~(~~)", REINDEER_BROWN));
+            let nose_color = if i == REINDEER_COUNT - 1 {
+                Color::Red
+            } else {
+                REINDEER_BROWN
+            };
+            parts.push((" ", ">", nose_color));
+        }
+
+        let mut convoy = vec![Vec::new(), Vec::new()];
+        for (top, bottom, color) in parts {
+            for (row, s) in [top, bottom].into_iter().enumerate() {
+                convoy[row].extend(s.chars().map(|c| match c {
+                    ' ' => Content::Transparent,
+                    _ => Content::ColoredString {
+                        s: c.to_string().color(color),
+                    },
+                }));
+            }
+        }
+
+        return convoy;
+    }
+
+    fn is_calling_out(&self, position: isize) -> bool {
+        if !self.bubble {
+            return false;
+        }
+
+        let center = position + self.convoy_width as isize / 2;
+        let third = self.frame_width as isize / 3;
+        return center >= third && center < 2 * third;
+    }
+}
+
+impl Default for SantaSleighFrame {
+    fn default() -> Self {
+        let convoy = SantaSleighFrame::build_convoy();
+        let convoy_width = convoy[0].len();
+        return SantaSleighFrame {
+            frame_width: 0,
+            interval: DEFAULT_SLEIGH_INTERVAL,
+            bubble: true,
+            convoy,
+            convoy_width,
+            position: None,
+            waiting: 0,
+        };
+    }
+}
+
+impl Frame for SantaSleighFrame {
+    fn update(&mut self, screen_width: usize, _screen_height: usize) {
+        self.frame_width = screen_width;
+
+        self.position = match self.position {
+            Some(position) if position < self.frame_width as isize => {
+                Some(position + COLUMNS_PER_UPDATE)
+            }
+            Some(_) => {
+                self.waiting = self.interval;
+                None
+            }
+            None if self.waiting == 0 => Some(-(self.convoy_width as isize)),
+            None => {
+                self.waiting -= 1;
+                None
+            }
+        };
+    }
+
+    fn content_bounds(&self) -> Option<Bounds> {
+        let Some(position) = self.position else {
+            return Some(Bounds::EMPTY);
+        };
+
+        // the bubble may reach past the front of the convoy
+        let width = match self.bubble {
+            true => self.convoy_width.max(SANTA_X + BUBBLE.len()),
+            false => self.convoy_width,
+        };
+        // clipped to the screen at both edges
+        let right = (position + width as isize).clamp(0, self.frame_width as isize) as usize;
+        let left = (position.max(0) as usize).min(right);
+        return Some(Bounds {
+            x: left,
+            y: CONVOY_Y - 1,
+            width: right - left,
+            height: 3,
+        });
+    }
+
+    fn get_content(&mut self, x: usize, y: usize) -> Content {
+        let Some(position) = self.position else {
+            return Content::Transparent;
+        };
+        if y + 1 < CONVOY_Y || y > CONVOY_Y + 1 {
+            return Content::Transparent;
+        }
+
+        let convoy_x = x as isize - position;
+
+        if y + 1 == CONVOY_Y {
+            let bubble_x = convoy_x - SANTA_X as isize;
+            if !self.is_calling_out(position) || bubble_x < 0 {
+                return Content::Transparent;
+            }
+            return match BUBBLE.chars().nth(bubble_x as usize) {
+                Some(' ') | None => Content::Transparent,
+                Some(c) => Content::ColoredString {
+                    s: c.to_string().bright_white(),
+                },
+            };
+        }
+
+        if convoy_x < 0 || convoy_x >= self.convoy_width as isize {
+            return Content::Transparent;
+        }
+        return self.convoy[y - CONVOY_Y][convoy_x as usize].clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIDTH: usize = 40;
+
+    fn row(sleigh: &mut SantaSleighFrame, y: usize) -> String {
+        return (0..WIDTH)
+            .map(|x| match sleigh.get_content(x, y) {
+                Content::ColoredString { s } => s.input,
+                _ => " ".to_owned(),
+            })
+            .collect();
+    }
+
+    fn flying(sleigh: &mut SantaSleighFrame, updates: usize) {
+        for _ in 0..updates {
+            sleigh.update(WIDTH, 10);
+        }
+    }
+
+    #[test]
+    fn flies_the_convoy_together_clipped_at_both_edges() {
+        let mut sleigh = SantaSleighFrame::default();
+        // taking off just out of sight, then showing only the lead reindeer
+        flying(&mut sleigh, 1);
+        assert_eq!(sleigh.content_bounds().unwrap().width, 0);
+        flying(&mut sleigh, 1);
+        let bounds = sleigh.content_bounds().unwrap();
+        assert_eq!((bounds.x, bounds.width), (0, 3));
+        assert_eq!(&row(&mut sleigh, CONVOY_Y + 1)[..3], "~)>");
+
+        // the sleigh a rein behind each reindeer, the bubble over the middle
+        flying(&mut sleigh, 8);
+        assert_eq!(
+            row(&mut sleigh, CONVOY_Y + 1).trim_end(),
+            " \\___/-~(~~)>-~(~~)>-~(~~)>"
+        );
+        assert_eq!(row(&mut sleigh, CONVOY_Y - 1).trim_end(), "   Ho Ho Ho!");
+
+        // leaving past the right edge
+        flying(&mut sleigh, 5);
+        let bounds = sleigh.content_bounds().unwrap();
+        assert_eq!(bounds.x + bounds.width, WIDTH);
+        assert_eq!(
+            row(&mut sleigh, CONVOY_Y + 1).trim(),
+            "\\___/-~(~~)>-~(~~)>-~(~~"
+        );
+    }
+
+    #[test]
+    fn flies_again_straight_away_without_an_interval() {
+        let mut sleigh = SantaSleighFrame::default()
+            .with_interval(0)
+            .with_bubble(false);
+        let mut take_offs = 0;
+        for _ in 0..60 {
+            sleigh.update(WIDTH, 10);
+            assert_eq!(row(&mut sleigh, CONVOY_Y - 1).trim(), "");
+            if sleigh.position == Some(-(sleigh.convoy_width as isize)) {
+                take_offs += 1;
+            }
+        }
+        // crossing in 22 updates, out of sight for one and taking off on the next
+        assert_eq!(take_offs, 3);
+    }
+}
@@ -0,0 +1,643 @@
+use std::ops::Range;
+
+use bit_set::BitSet;
+use colored::{Color, Colorize};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    color,
+    frame::{Content, Frame},
+    scene::Scene,
+};
+
+// how many columns a landed flake may be blown per column of wind
+const DRIFT_DISTANCE: usize = 4;
+// how strongly a light above tints the ground snow
+const REFLECTION_STRENGTH: f32 = 0.35;
+// chance of a flake starting to fall from each column per update
+const FLAKE_CHANCE: f32 = 1.0 / 21.0;
+// fraction of the way to the scene's snowfall the intensity eases each update
+const SNOWFALL_EASING: f32 = 0.1;
+// slowest a flake may fall, in rows per update
+const MIN_FALL_SPEED: f32 = 0.1;
+// brightness of the slowest flakes when their speeds vary, the fastest being white
+const SLOW_FLAKE_BRIGHTNESS: f32 = 0.6;
+// chance of a flake sparkling out with --sparkle, and its brightness on each
+// update from when it twinkles until it is gone
+const SPARKLE_CHANCE: f32 = 1.0 / 30.0;
+const SPARKLE_BRIGHTNESS: [f32; 3] = [1.0, 0.55, 0.25];
+
+struct Flake {
+    x: usize,
+    y: f32,
+    // row the flake was on before its last fall
+    last_row: usize,
+    speed: f32,
+    sparkle: Option<Sparkle>,
+}
+
+// a flake that twinkles on reaching a row and fades out there instead of
+// falling any further
+struct Sparkle {
+    row: f32,
+    // updates since it twinkled, none before
+    age: Option<usize>,
+}
+
+impl Flake {
+    fn row(&self) -> usize {
+        return self.y as usize;
+    }
+}
+
+pub struct SnowFrame {
+    rng: StdRng,
+    frame_width: usize,
+    frame_height: usize,
+    flakes: Vec<Flake>,
+    // how thickly snow falls compared to usual, by the scene and on its own
+    intensity: f32,
+    density: f32,
+    // fall speed of new flakes is drawn evenly from this range, in rows per update
+    min_speed: f32,
+    max_speed: f32,
+    // brightness of the brightest flakes, and what they are drawn as
+    brightness: f32,
+    glyph: char,
+    // glyph and color of the flake drawn at each cell, row by row
+    drawn: Vec<Option<(char, Color)>>,
+    sparkle: bool,
+    from_clouds: bool,
+    // columns under the clouds of the scene, as of the last tick
+    clouds: Vec<Range<usize>>,
+    prefill: bool,
+    settling: bool,
+    ground_snow: bool,
+    drift: bool,
+    ground: Vec<usize>,
+    // snow settled on the surfaces of other frames, by screen row
+    caps: Vec<BitSet>,
+    reflections: bool,
+    // color of the nearest light above the ground snow of each column
+    reflected: Vec<Option<Color>>,
+}
+
+impl SnowFrame {
+    /// Makes the snowfall reproducible instead of seeding it from entropy.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        return self;
+    }
+
+    /// Lets `density` times the usual number of flakes fall. Fails if it is
+    /// negative or infinite.
+    pub fn with_density(mut self, density: f32) -> Result<Self, String> {
+        if !(density >= 0.0 && density.is_finite()) {
+            return Err(format!(
+                "snow density must be zero or more, got {}",
+                density
+            ));
+        }
+
+        self.density = density;
+        return Ok(self);
+    }
+
+    /// Dims every flake to `brightness`, from `0.0` (black) to `1.0` (white,
+    /// as usual). Any other value, NaN included, is an error.
+    pub fn with_brightness(mut self, brightness: f32) -> Result<Self, String> {
+        if !(0.0..=1.0).contains(&brightness) {
+            return Err(format!(
+                "snow brightness must be from 0 to 1, got {}",
+                brightness
+            ));
+        }
+
+        self.brightness = brightness;
+        return Ok(self);
+    }
+
+    /// Draws flakes as `glyph` instead of `o`, e.g. `.` for smaller ones.
+    pub fn with_glyph(mut self, glyph: char) -> Self {
+        self.glyph = glyph;
+        return self;
+    }
+
+    /// Lets the odd flake twinkle partway down and fade away instead of
+    /// falling all the way, picked by the seed like the rest of the snowfall.
+    pub fn with_sparkle(mut self, sparkle: bool) -> Self {
+        self.sparkle = sparkle;
+        return self;
+    }
+
+    /// Lets flakes only start falling under the clouds other frames report
+    /// rather than all across the sky. Until a frame reports any, they fall
+    /// from all over.
+    pub fn with_clouds(mut self, from_clouds: bool) -> Self {
+        self.from_clouds = from_clouds;
+        return self;
+    }
+
+    /// Lets flakes settle on the surfaces of other frames, such as the
+    /// branches of the tree, on by default. Turned off the flakes fall past
+    /// them, as if nearer than the frames.
+    pub fn with_settling(mut self, settling: bool) -> Self {
+        self.settling = settling;
+        return self;
+    }
+
+    /// Starts with the sky already full of falling snow, as if it had been
+    /// snowing for a while, rather than with the first flakes at the top.
+    /// It starts over whenever the frame is resized.
+    pub fn with_prefill(mut self, prefill: bool) -> Self {
+        self.prefill = prefill;
+        return self;
+    }
+
+    /// Lets flakes pile up on the bottom of the screen instead of falling through.
+    pub fn with_ground_snow(mut self, ground_snow: bool) -> Self {
+        self.ground_snow = ground_snow;
+        return self;
+    }
+
+    /// Lets the wind blow landed snow downwind before it settles, so it piles
+    /// up against the downwind edge and in the shelter behind solid frames.
+    /// Implies ground snow.
+    pub fn with_drift(mut self, drift: bool) -> Self {
+        self.drift = drift;
+        self.ground_snow |= drift;
+        return self;
+    }
+
+    /// Lets every flake fall at its own speed, drawn evenly from `min` to
+    /// `max` rows per update when it starts falling. Faster flakes, being
+    /// nearer, are drawn brighter. Both fall one row per update by default.
+    ///
+    /// Fails if `min` is below 0.1 rows per update, where a flake would
+    /// hang still for a long while, or `max` is below `min`.
+    pub fn with_fall_speed(mut self, min: f32, max: f32) -> Result<Self, String> {
+        if !(min >= MIN_FALL_SPEED && min.is_finite()) {
+            return Err(format!(
+                "fall speed must be at least {} rows per update, got {}",
+                MIN_FALL_SPEED, min
+            ));
+        }
+        if !(max >= min && max.is_finite()) {
+            return Err(format!(
+                "maximum fall speed must be at least the minimum {}, got {}",
+                min, max
+            ));
+        }
+
+        self.min_speed = min;
+        self.max_speed = max;
+        return Ok(self);
+    }
+
+    /// Tints the ground snow with the color of the lights right above it,
+    /// such as the ornaments of the tree.
+    pub fn with_reflections(mut self, reflections: bool) -> Self {
+        self.reflections = reflections;
+        return self;
+    }
+
+    /// Height of the snow pile on each column.
+    pub fn ground(&self) -> &[usize] {
+        return &self.ground;
+    }
+
+    fn max_ground_height(&self) -> usize {
+        return self.frame_height / 4;
+    }
+
+    // row a flake landing on column `x` comes to rest on
+    fn pile_top(&self, x: usize) -> usize {
+        return self.frame_height - 1 - self.ground[x];
+    }
+
+    fn under_cloud(&self, x: usize) -> bool {
+        return !self.from_clouds
+            || self.clouds.is_empty()
+            || self.clouds.iter().any(|columns| columns.contains(&x));
+    }
+
+    fn fall(&mut self) {
+        for flake in self.flakes.iter_mut() {
+            flake.last_row = flake.row();
+            match &mut flake.sparkle {
+                Some(Sparkle { age: Some(age), .. }) => *age += 1,
+                Some(sparkle) if flake.y + flake.speed >= sparkle.row => {
+                    flake.y = sparkle.row;
+                    sparkle.age = Some(0);
+                }
+                _ => flake.y += flake.speed,
+            }
+        }
+        let height = self.frame_height;
+        self.flakes.retain(|flake| {
+            let faded = flake
+                .sparkle
+                .as_ref()
+                .and_then(|sparkle| sparkle.age)
+                .is_some_and(|age| age >= SPARKLE_BRIGHTNESS.len());
+            return flake.row() < height && !faded;
+        });
+
+        for x in 0..self.frame_width {
+            if !self.under_cloud(x) {
+                continue;
+            }
+            if self.rng.gen::<f32>() < FLAKE_CHANCE * self.density * self.intensity {
+                let speed = if self.max_speed > self.min_speed {
+                    self.rng.gen_range(self.min_speed..=self.max_speed)
+                } else {
+                    self.min_speed
+                };
+                let sparkle = (self.sparkle && self.rng.gen::<f32>() < SPARKLE_CHANCE).then(|| {
+                    return Sparkle {
+                        row: self.rng.gen_range(0.2..0.8) * self.frame_height as f32,
+                        age: None,
+                    };
+                });
+                self.flakes.push(Flake {
+                    x,
+                    y: 0.0,
+                    last_row: 0,
+                    speed,
+                    sparkle,
+                });
+            }
+        }
+    }
+
+    fn blow(&mut self, wind: isize) {
+        let width = self.frame_width as isize;
+        for flake in self.flakes.iter_mut() {
+            flake.x = (flake.x as isize + wind).rem_euclid(width) as usize;
+        }
+    }
+
+    // caps each surface with a single row of snow, or each ledge with as many
+    // rows as it takes, further flakes melting into it
+    fn settle(&mut self, scene: &Scene) {
+        // bottom up, for snow lying on melted snow to melt along with it
+        for y in (0..self.frame_height).rev() {
+            // the surface may have moved or gone away
+            let melted = self.caps[y]
+                .iter()
+                .filter(|x| {
+                    let on_snow = self.caps.get(y + 1).is_some_and(|below| below.contains(*x));
+                    return !scene.is_surface(*x, y + 1) && !on_snow;
+                })
+                .collect::<Vec<usize>>();
+            for x in melted {
+                self.caps[y].remove(x);
+            }
+        }
+
+        let caps = &mut self.caps;
+        self.flakes.retain(|flake| {
+            let x = flake.x;
+            let lying = |y: usize| caps.get(y).is_some_and(|cap| cap.contains(x));
+            // a fast flake must not fall through a surface it skipped over
+            let settled_on =
+                (flake.last_row..=flake.row()).find(|y| scene.is_surface(x, y + 1) || lying(y + 1));
+            let Some(y) = settled_on else {
+                return true;
+            };
+
+            let depth = (y + 1..).take_while(|y| lying(*y)).count();
+            if depth < scene.ledge_height(x, y + 1 + depth) {
+                caps[y].insert(x);
+            }
+            return false;
+        });
+    }
+
+    fn draw(&mut self) {
+        self.drawn = vec![None; self.frame_width * self.frame_height];
+        let spread = self.max_speed - self.min_speed;
+        for flake in self.flakes.iter() {
+            let brightness = if spread > 0.0 {
+                let nearness = (flake.speed - self.min_speed) / spread;
+                SLOW_FLAKE_BRIGHTNESS + (1.0 - SLOW_FLAKE_BRIGHTNESS) * nearness
+            } else {
+                1.0
+            };
+            let twinkled = flake.sparkle.as_ref().and_then(|sparkle| sparkle.age);
+            self.drawn[flake.row() * self.frame_width + flake.x] = Some(match twinkled {
+                Some(age) => (
+                    '*',
+                    color::scale(Color::White, SPARKLE_BRIGHTNESS[age] * self.brightness),
+                ),
+                None => (
+                    self.glyph,
+                    color::scale(Color::White, brightness * self.brightness),
+                ),
+            });
+        }
+    }
+
+    fn reflect(&mut self, scene: &Scene) {
+        self.reflected = vec![None; self.frame_width];
+        let mut nearest = vec![None; self.frame_width];
+        for light in scene.lights() {
+            if light.x >= self.frame_width || light.y > self.pile_top(light.x) {
+                continue;
+            }
+            if nearest[light.x].is_none_or(|y| light.y > y) {
+                nearest[light.x] = Some(light.y);
+                self.reflected[light.x] = Some(light.color);
+            }
+        }
+    }
+
+    fn land(&mut self, scene: &Scene) {
+        let (landed, falling): (Vec<Flake>, Vec<Flake>) = std::mem::take(&mut self.flakes)
+            .into_iter()
+            .partition(|flake| flake.row() >= self.pile_top(flake.x));
+        self.flakes = falling;
+
+        for flake in landed {
+            self.deposit(flake.x, scene);
+        }
+    }
+
+    fn deposit(&mut self, x: usize, scene: &Scene) {
+        let mut x = x;
+
+        if self.drift && scene.wind != 0 {
+            let step = scene.wind.signum();
+            for _ in 0..scene.wind.unsigned_abs() * DRIFT_DISTANCE {
+                // sheltered in the lee of an obstacle
+                let upwind = x.wrapping_add_signed(-step);
+                if scene.is_solid(upwind, self.pile_top(x)) {
+                    break;
+                }
+
+                // piled against the screen edge, an obstacle or a steep drift
+                let next = x.wrapping_add_signed(step);
+                if next >= self.frame_width
+                    || scene.is_solid(next, self.pile_top(next))
+                    || self.ground[next] > self.ground[x] + 1
+                {
+                    break;
+                }
+
+                x = next;
+            }
+        }
+
+        if self.ground[x] < self.max_ground_height() {
+            self.ground[x] += 1;
+        }
+    }
+}
+
+impl Default for SnowFrame {
+    fn default() -> Self {
+        return SnowFrame {
+            rng: StdRng::from_entropy(),
+            frame_width: 0,
+            frame_height: 0,
+            flakes: Vec::new(),
+            intensity: 1.0,
+            density: 1.0,
+            min_speed: 1.0,
+            max_speed: 1.0,
+            brightness: 1.0,
+            glyph: 'o',
+            drawn: Vec::new(),
+            sparkle: false,
+            from_clouds: false,
+            clouds: Vec::new(),
+            prefill: false,
+            settling: true,
+            ground_snow: false,
+            drift: false,
+            ground: Vec::new(),
+            caps: Vec::new(),
+            reflections: false,
+            reflected: Vec::new(),
+        };
+    }
+}
+
+impl Frame for SnowFrame {
+    fn update(&mut self, screen_width: usize, screen_height: usize) {
+        if self.frame_width != screen_width || self.frame_height != screen_height {
+            self.frame_width = screen_width;
+            self.frame_height = screen_height;
+            self.flakes.clear();
+            self.ground = vec![0; screen_width];
+            self.caps = vec![BitSet::with_capacity(screen_width); screen_height];
+
+            // long enough for the slowest flakes to reach the bottom
+            if self.prefill {
+                for _ in 0..(screen_height as f32 / self.min_speed).ceil() as usize {
+                    self.fall();
+                }
+            }
+        }
+
+        self.fall();
+    }
+
+    fn snow_depths(&self) -> Vec<usize> {
+        return self.ground.clone();
+    }
+
+    fn tick(&mut self, scene: &Scene) {
+        let target = scene.scaled_snowfall();
+        self.intensity += (target - self.intensity) * SNOWFALL_EASING;
+
+        if scene.wind != 0 {
+            self.blow(scene.wind);
+        }
+
+        if self.from_clouds {
+            self.clouds = scene.clouds().to_vec();
+        }
+        if self.settling {
+            self.settle(scene);
+        }
+        if self.ground_snow {
+            self.land(scene);
+        }
+        if self.reflections {
+            self.reflect(scene);
+        }
+        self.draw();
+    }
+
+    fn get_content(&mut self, x: usize, y: usize) -> Content {
+        if self.ground_snow && y > self.pile_top(x) {
+            let reflected = self.reflected.get(x).copied().flatten();
+            return match reflected {
+                Some(light) if y == self.pile_top(x) + 1 => Content::ColoredString {
+                    s: "#".color(color::mix(Color::White, light, REFLECTION_STRENGTH)),
+                },
+                _ => Content::ColoredString { s: "#".white() },
+            };
+        }
+
+        if self.caps[y].contains(x) {
+            // snow on a ledge is only topped off on its top row
+            let topped = y
+                .checked_sub(1)
+                .is_some_and(|above| self.caps[above].contains(x));
+            let glyph = if topped { "#" } else { "_" };
+            return Content::ColoredString { s: glyph.white() };
+        }
+
+        return match self.drawn.get(y * self.frame_width + x) {
+            Some(Some((glyph, color))) => Content::ColoredString {
+                s: glyph.to_string().color(*color),
+            },
+            _ => Content::Transparent,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::{Ledge, Surface};
+
+    #[test]
+    fn rejects_impossible_fall_speeds() {
+        let error = SnowFrame::default().with_fall_speed(0.0, 1.0).err();
+        assert_eq!(
+            error.as_deref(),
+            Some("fall speed must be at least 0.1 rows per update, got 0")
+        );
+        let error = SnowFrame::default().with_fall_speed(2.0, 1.0).err();
+        assert_eq!(
+            error.as_deref(),
+            Some("maximum fall speed must be at least the minimum 2, got 1")
+        );
+        assert!(SnowFrame::default().with_fall_speed(0.5, f32::NAN).is_err());
+        assert!(SnowFrame::default().with_fall_speed(0.5, 2.0).is_ok());
+    }
+
+    #[test]
+    fn sparkles_out_partway_down() {
+        let mut snow = SnowFrame::default()
+            .with_sparkle(true)
+            .with_density(0.0)
+            .unwrap();
+        snow.update(1, 10);
+        snow.flakes.push(Flake {
+            x: 0,
+            y: 0.0,
+            last_row: 0,
+            speed: 1.0,
+            sparkle: Some(Sparkle {
+                row: 3.5,
+                age: None,
+            }),
+        });
+        let scene = Scene::default();
+        let mut seen = Vec::new();
+        for _ in 0..8 {
+            snow.update(1, 10);
+            snow.tick(&scene);
+            seen.push((0..10).find_map(|y| match snow.get_content(0, y) {
+                Content::ColoredString { s } => Some((y, s.input)),
+                _ => None,
+            }));
+        }
+
+        let twinkle = (3, "*".to_owned());
+        assert_eq!(
+            seen,
+            [
+                Some((1, "o".to_owned())),
+                Some((2, "o".to_owned())),
+                Some((3, "o".to_owned())),
+                Some(twinkle.clone()),
+                Some(twinkle.clone()),
+                Some(twinkle),
+                None,
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn piles_snow_on_a_ledge_up_to_its_height() {
+        let mut snow = SnowFrame::default().with_density(0.0).unwrap();
+        let mut scene = Scene::default();
+        scene.reset(2, 6);
+        let sill = Surface {
+            x: 0,
+            y: 5,
+            width: 2,
+        };
+        scene.add_surface(&sill);
+        scene.add_ledge(&Ledge {
+            surface: Surface { width: 1, ..sill },
+            height: 2,
+        });
+        snow.update(2, 6);
+        for _ in 0..3 {
+            for x in 0..2 {
+                snow.flakes.push(Flake {
+                    x,
+                    y: 0.0,
+                    last_row: 0,
+                    speed: 1.0,
+                    sparkle: None,
+                });
+            }
+            for _ in 0..6 {
+                snow.update(2, 6);
+                snow.tick(&scene);
+            }
+        }
+
+        let column = |snow: &mut SnowFrame, x| {
+            return (2..5)
+                .map(|y| match snow.get_content(x, y) {
+                    Content::ColoredString { s } => s.input,
+                    _ => " ".to_owned(),
+                })
+                .collect::<String>();
+        };
+        // the ledge takes two rows, the rest of the sill a single cap
+        assert_eq!(column(&mut snow, 0), " _#");
+        assert_eq!(column(&mut snow, 1), "  _");
+        assert!(snow.flakes.is_empty());
+    }
+
+    #[test]
+    fn falls_only_from_under_the_clouds() {
+        let mut snow = SnowFrame::default()
+            .with_seed(1)
+            .with_density(50.0)
+            .unwrap()
+            .with_clouds(true);
+        let mut scene = Scene::default();
+        scene.reset(10, 4);
+        // with no clouds yet it snows from all over
+        snow.update(10, 4);
+        assert!(snow.flakes.iter().any(|flake| !(2..5).contains(&flake.x)));
+
+        scene.add_cloud(2..5);
+        snow.tick(&scene);
+        snow.flakes.clear();
+        snow.update(10, 4);
+        assert!(!snow.flakes.is_empty());
+        assert!(snow.flakes.iter().all(|flake| (2..5).contains(&flake.x)));
+    }
+
+    #[test]
+    fn rejects_negative_densities() {
+        let error = SnowFrame::default().with_density(-1.0).err();
+        assert_eq!(
+            error.as_deref(),
+            Some("snow density must be zero or more, got -1")
+        );
+        assert!(SnowFrame::default().with_density(0.0).is_ok());
+    }
+}
@@ -0,0 +1,194 @@
+use std::time::Duration;
+
+use colored::{Color, Colorize};
+
+use crate::{
+    frame::{Bounds, Content, Frame},
+    scene::{Scene, Surface},
+};
+
+// rows of the snowman top to bottom, ' ' being see-through
+const SPRITE: [&str; 5] = ["  ___  ", " _|_|_ ", " (o o) ", "(  :  )", "(  :  )"];
+const WIDTH: usize = 7;
+const PUDDLE: &str = "~~~~~~~";
+
+/// A snowman standing on the ground to the right of the tree. Left to melt
+/// he shrinks row by row from the hat down until only a puddle is left.
+pub struct SnowmanFrame {
+    frame_width: usize,
+    frame_height: usize,
+    // how long he takes to melt down to a puddle, none for never
+    melt: Option<Duration>,
+    reset: bool,
+    // rows of him still standing, from the bottom of the sprite
+    height: usize,
+}
+
+impl SnowmanFrame {
+    /// Melts him down to a puddle over `duration` by the scene's clock.
+    /// Fails if `duration` is zero.
+    pub fn with_melt(mut self, duration: Duration) -> Result<Self, String> {
+        if duration.is_zero() {
+            return Err("melt duration must be longer than zero".to_owned());
+        }
+
+        self.melt = Some(duration);
+        return Ok(self);
+    }
+
+    /// Builds him up again once melted, for the melt to start over.
+    pub fn with_melt_reset(mut self, reset: bool) -> Self {
+        self.reset = reset;
+        return self;
+    }
+
+    fn left(&self) -> usize {
+        return (self.frame_width * 7 / 8).saturating_sub(WIDTH / 2);
+    }
+
+    // row of the top of what is still standing, or of the puddle
+    fn top(&self) -> usize {
+        return self.frame_height.saturating_sub(self.height.max(1));
+    }
+
+    // the sprite row shown at row `y`, if he stands there
+    fn sprite_row(&self, y: usize) -> Option<&'static str> {
+        if y >= self.frame_height || y < self.top() || self.height == 0 {
+            return None;
+        }
+
+        let rows_from_bottom = self.frame_height - 1 - y;
+        return Some(SPRITE[SPRITE.len() - 1 - rows_from_bottom]);
+    }
+}
+
+impl Default for SnowmanFrame {
+    fn default() -> Self {
+        return SnowmanFrame {
+            frame_width: 0,
+            frame_height: 0,
+            melt: None,
+            reset: false,
+            height: SPRITE.len(),
+        };
+    }
+}
+
+impl Frame for SnowmanFrame {
+    fn update(&mut self, screen_width: usize, screen_height: usize) {
+        self.frame_width = screen_width;
+        self.frame_height = screen_height;
+    }
+
+    fn tick(&mut self, scene: &Scene) {
+        let Some(melt) = self.melt else {
+            return;
+        };
+
+        // the puddle gets a turn as long as each row before starting over
+        let turn = melt / SPRITE.len() as u32;
+        let elapsed = if self.reset {
+            Duration::from_nanos((scene.time.as_nanos() % (melt + turn).as_nanos()) as u64)
+        } else {
+            scene.time
+        };
+        let melted = (elapsed.as_secs_f32() / turn.as_secs_f32()) as usize;
+        self.height = SPRITE.len().saturating_sub(melted);
+    }
+
+    fn is_solid(&self, x: usize, y: usize) -> bool {
+        let Some(row) = self.sprite_row(y) else {
+            return false;
+        };
+
+        return x
+            .checked_sub(self.left())
+            .and_then(|x| row.as_bytes().get(x))
+            .is_some_and(|c| *c != b' ');
+    }
+
+    fn solid_surfaces(&self) -> Vec<Surface> {
+        let Some(row) = self.sprite_row(self.top()) else {
+            return Vec::new();
+        };
+
+        let start = row.len() - row.trim_start().len();
+        return vec![Surface {
+            x: self.left() + start,
+            y: self.top(),
+            width: row.trim().len(),
+        }];
+    }
+
+    fn content_bounds(&self) -> Option<Bounds> {
+        return Some(Bounds {
+            x: self.left(),
+            y: self.top(),
+            width: WIDTH,
+            height: self.height.max(1),
+        });
+    }
+
+    fn get_content(&mut self, x: usize, y: usize) -> Content {
+        let Some(column) = x.checked_sub(self.left()).filter(|x| *x < WIDTH) else {
+            return Content::Transparent;
+        };
+
+        if self.height == 0 {
+            if y + 1 != self.frame_height {
+                return Content::Transparent;
+            }
+            return Content::ColoredString {
+                s: PUDDLE[column..column + 1].bright_cyan(),
+            };
+        }
+
+        let Some(row) = self.sprite_row(y) else {
+            return Content::Transparent;
+        };
+        return match row.as_bytes()[column] {
+            b' ' => Content::Transparent,
+            b'o' => Content::ColoredString { s: "o".black() },
+            c @ (b'_' | b'|') => Content::ColoredString {
+                s: (c as char).to_string().color(Color::BrightBlack),
+            },
+            c => Content::ColoredString {
+                s: (c as char).to_string().bright_white(),
+            },
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn melts_row_by_row_into_a_puddle() {
+        let mut snowman = SnowmanFrame::default()
+            .with_melt(Duration::from_secs(50))
+            .unwrap();
+        snowman.update(40, 10);
+        let mut scene = Scene::default();
+        for (seconds, height) in [(0, 5), (10, 4), (45, 1), (50, 0), (100, 0)] {
+            scene.time = Duration::from_secs(seconds);
+            snowman.tick(&scene);
+            assert_eq!(snowman.height, height, "after {} seconds", seconds);
+        }
+
+        let puddle = snowman.get_content(snowman.left(), 9);
+        assert!(matches!(puddle, Content::ColoredString { s } if s.input == "~"));
+    }
+
+    #[test]
+    fn builds_up_again_when_reset() {
+        let mut snowman = SnowmanFrame::default()
+            .with_melt(Duration::from_secs(50))
+            .unwrap()
+            .with_melt_reset(true);
+        let mut scene = Scene::default();
+        scene.time = Duration::from_secs(61);
+        snowman.tick(&scene);
+        assert_eq!(snowman.height, 5);
+    }
+}
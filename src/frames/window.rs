@@ -0,0 +1,86 @@
+use colored::{Color, Colorize};
+
+use crate::{
+    frame::{Content, Frame},
+    scene::{Ledge, Surface},
+};
+
+const WOOD: Color = Color::TrueColor {
+    r: 150,
+    g: 100,
+    b: 55,
+};
+
+/// Default rows of snow the sill takes before further flakes melt into it.
+pub const DEFAULT_WINDOW_LEDGE: usize = 3;
+
+/// A wooden window frame around the edges of the screen, for looking out at
+/// the card through a window. Snow settles on its sill in a ledge.
+pub struct WindowFrame {
+    frame_width: usize,
+    frame_height: usize,
+    ledge_height: usize,
+}
+
+impl WindowFrame {
+    /// Lets the snow on the sill build up to `ledge_height` rows deep. Fails
+    /// unless it is at least a row.
+    pub fn with_ledge_height(mut self, ledge_height: usize) -> Result<Self, String> {
+        if ledge_height == 0 {
+            return Err("the window ledge must be at least a row deep".to_owned());
+        }
+
+        self.ledge_height = ledge_height;
+        return Ok(self);
+    }
+}
+
+impl Default for WindowFrame {
+    fn default() -> Self {
+        return WindowFrame {
+            frame_width: 0,
+            frame_height: 0,
+            ledge_height: DEFAULT_WINDOW_LEDGE,
+        };
+    }
+}
+
+impl Frame for WindowFrame {
+    fn update(&mut self, screen_width: usize, screen_height: usize) {
+        self.frame_width = screen_width;
+        self.frame_height = screen_height;
+    }
+
+    fn snow_ledges(&self) -> Vec<Ledge> {
+        let Some(sill_y) = self.frame_height.checked_sub(1) else {
+            return Vec::new();
+        };
+
+        // between the posts on either side
+        return vec![Ledge {
+            surface: Surface {
+                x: 1,
+                y: sill_y,
+                width: self.frame_width.saturating_sub(2),
+            },
+            height: self.ledge_height,
+        }];
+    }
+
+    fn get_content(&mut self, x: usize, y: usize) -> Content {
+        let (left, right) = (x == 0, x + 1 == self.frame_width);
+        let (top, bottom) = (y == 0, y + 1 == self.frame_height);
+        let glyph = match (left, right, top, bottom) {
+            (true, _, true, _) => "╔",
+            (_, true, true, _) => "╗",
+            (true, _, _, true) => "╚",
+            (_, true, _, true) => "╝",
+            (_, _, true, _) | (_, _, _, true) => "═",
+            (true, _, _, _) | (_, true, _, _) => "║",
+            _ => return Content::Transparent,
+        };
+        return Content::ColoredString {
+            s: glyph.color(WOOD),
+        };
+    }
+}
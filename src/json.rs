@@ -0,0 +1,89 @@
+use std::{iter::Peekable, str::Chars};
+
+/// Skips any whitespace `chars` starts with.
+pub fn skip_space(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|c| c.is_ascii_whitespace()).is_some() {}
+}
+
+/// Consumes `expected` after any whitespace, failing if anything else comes.
+pub fn expect(chars: &mut Peekable<Chars>, expected: char) -> Result<(), String> {
+    skip_space(chars);
+    return match chars.next() {
+        Some(c) if c == expected => Ok(()),
+        Some(c) => Err(format!("expected `{}`, found `{}`", expected, c)),
+        None => Err(format!(
+            "expected `{}`, found the end of the line",
+            expected
+        )),
+    };
+}
+
+/// Parses the JSON string `chars` starts with, leaving what follows it.
+pub fn parse_string(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    if chars.next() != Some('"') {
+        return Err("expected a string in double quotes".to_owned());
+    }
+
+    let mut s = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(s),
+            Some('\\') => {
+                let c = match chars.next() {
+                    Some('"') => '"',
+                    Some('\\') => '\\',
+                    Some('/') => '/',
+                    Some('b') => '\u{8}',
+                    Some('f') => '\u{c}',
+                    Some('n') => '\n',
+                    Some('r') => '\r',
+                    Some('t') => '\t',
+                    Some('u') => {
+                        let mut code = parse_hex(chars)?;
+                        // characters past the first plane come as a surrogate pair
+                        if (0xd800..0xdc00).contains(&code) {
+                            if chars.next() != Some('\\') || chars.next() != Some('u') {
+                                return Err("unpaired surrogate in a string".to_owned());
+                            }
+                            let low = parse_hex(chars)?;
+                            if !(0xdc00..0xe000).contains(&low) {
+                                return Err("unpaired surrogate in a string".to_owned());
+                            }
+                            code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                        }
+                        char::from_u32(code).ok_or("invalid \\u escape in a string")?
+                    }
+                    _ => return Err("invalid escape in a string".to_owned()),
+                };
+                s.push(c);
+            }
+            Some(c) => s.push(c),
+            None => return Err("unterminated string".to_owned()),
+        }
+    }
+}
+
+fn parse_hex(chars: &mut Peekable<Chars>) -> Result<u32, String> {
+    let hex: String = chars.take(4).collect();
+    if hex.len() != 4 {
+        return Err("invalid \\u escape in a string".to_owned());
+    }
+    return u32::from_str_radix(&hex, 16).map_err(|_| "invalid \\u escape in a string".to_owned());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(s: &str) -> Result<String, String> {
+        return parse_string(&mut s.chars().peekable());
+    }
+
+    #[test]
+    fn joins_surrogate_pairs_and_rejects_unpaired_ones() {
+        assert_eq!(parsed(r#""🎄 ❄\n""#).as_deref(), Ok("🎄 ❄\n"));
+        for unpaired in [r#""\ud800A""#, r#""\ud800x""#, r#""\udc00""#] {
+            assert!(parsed(unpaired).is_err(), "{}", unpaired);
+        }
+    }
+}
@@ -0,0 +1,41 @@
+#![allow(clippy::needless_return)]
+//! A christmas card for the terminal: a twinkling tree under falling snow.
+//!
+//! The card is a stack of [`Frame`]s composited by a [`Printer`]. The binary
+//! draws it full screen, but the printer can also hand the picture out as
+//! lines or cells for embedding in another terminal UI, and with the
+//! `ratatui` feature as a ready-made `CardWidget`. Frames of your own can be
+//! added by name with `register_frame`. The `image` feature turns
+//! pictures into images with `rasterize`, for making videos of the card.
+
+mod blessing;
+pub mod color;
+mod date;
+mod frame;
+mod frames;
+mod printer;
+#[cfg(feature = "image")]
+mod raster;
+mod registry;
+mod scene;
+mod string_width;
+#[cfg(feature = "ratatui")]
+mod widget;
+
+pub use blessing::{blessing_template, BLESSINGS, DEFAULT_BLESSING_TEMPLATE};
+pub use date::Date;
+pub use frame::{string_to_content_vec, Bounds, Content, Frame};
+pub use frames::{
+    AuroraFrame, BannerFrame, BlessingAlign, CandlesFrame, ChristmasTreeFrame, CloudsFrame,
+    CountdownFrame, DebugFrame, FireworksFrame, GarlandFrame, GiftsFrame, GreetingFrame,
+    MirrorFrame, ParallaxFrame, SantaSleighFrame, SnowFrame, SnowmanFrame, TreeShape, WindowFrame,
+    DEFAULT_ORNAMENT_DENSITY, DEFAULT_SLEIGH_INTERVAL, DEFAULT_WINDOW_LEDGE, MIRRORED_GLYPHS,
+};
+pub use printer::{Cell, Printer};
+#[cfg(feature = "image")]
+pub use raster::{rasterize, Image, CELL_HEIGHT, CELL_WIDTH};
+pub use registry::{create_frame, frame_names, register_frame, FrameFactory};
+pub use scene::{Event, Ledge, Light, Scene, Surface};
+pub use string_width::{char_width, StringWidth};
+#[cfg(feature = "ratatui")]
+pub use widget::CardWidget;
@@ -1,304 +1,590 @@
-use std::{process::Command, thread, time::Duration};
-
-use bit_set::BitSet;
-use colored::{Color, ColoredString, Colorize};
-use rand::{rngs::ThreadRng, thread_rng, Rng};
-
-const BROWN: Color = Color::TrueColor {
-    r: 139,
-    g: 69,
-    b: 19,
+#![allow(clippy::needless_return)]
+
+mod budget;
+mod cli;
+mod config;
+mod control;
+mod first_snow;
+mod json;
+mod recording;
+mod terminal;
+
+use std::{
+    env,
+    io::{self, IsTerminal, Write},
+    process,
+    time::{Duration, Instant},
 };
 
-trait StringWidth {
-    fn width(&self) -> usize;
-}
+use budget::{Adjustment, FrameBudget};
+use cli::{Downgrade, Layout, Options, Parsed, RenderMode, SceneKind};
+use control::Controls;
+use cyber_christmas_card::{
+    blessing_template, create_frame, AuroraFrame, BannerFrame, CandlesFrame, ChristmasTreeFrame,
+    CloudsFrame, CountdownFrame, Date, DebugFrame, FireworksFrame, Frame, GarlandFrame, GiftsFrame,
+    GreetingFrame, ParallaxFrame, Printer, SantaSleighFrame, SnowFrame, SnowmanFrame, WindowFrame,
+};
+use recording::Recording;
+use terminal::Session;
+
+const FADE_DURATION: Duration = Duration::from_millis(1000);
+const FADE_STEPS: u32 = 10;
+// largest scene the compact layout shows
+const COMPACT_SIZE: (usize, usize) = (60, 20);
+// seconds the new year countdown counts down before the fireworks
+const COUNTDOWN_SECONDS: u64 = 10;
+// the near layer of --layered-snow has this fraction of the flakes, falling this much faster
+const NEAR_SNOW_DENSITY: f32 = 0.25;
+const NEAR_SNOW_SPEEDUP: f32 = 1.5;
+// the furthest of several --snow-layers keeps this fraction of the speed, brightness and
+// flakes of the first, the layers between easing evenly from one to the other
+const FAR_SNOW_SPEED: f32 = 0.4;
+const FAR_SNOW_BRIGHTNESS: f32 = 0.5;
+const FAR_SNOW_DENSITY: f32 = 0.5;
+// how thickly snow falls with --focus-reactive while the terminal has focus and while not
+const FOCUSED_SNOWFALL: f32 = 2.0;
+const UNFOCUSED_SNOWFALL: f32 = 0.3;
+// how much thinner each snowfall downgrade of --max-frame-ms leaves the snow
+const DOWNGRADED_SNOWFALL: f32 = 0.5;
 
-impl StringWidth for String {
-    fn width(&self) -> usize {
-        return self
-            .chars()
-            .map(|c| {
-                if c.is_ascii() {
-                    return 1;
-                } else {
-                    return 2;
-                }
-            })
-            .sum();
+fn main() {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(Parsed::Run(options)) => options,
+        Ok(Parsed::Help) => {
+            print!("{}", cli::USAGE);
+            return;
+        }
+        Err(message) => {
+            eprintln!("error: {}", message);
+            process::exit(2);
+        }
+    };
+    // before anything is drawn, for the escape codes to be understood on Windows
+    terminal::ansi_supported();
+
+    let mut snow_frame = SnowFrame::default()
+        .with_fall_speed(options.snow_speed.min, options.snow_speed.max)
+        .unwrap_or_else(|message| invalid(&message))
+        .with_prefill(options.snow_prefill)
+        .with_sparkle(options.sparkle)
+        .with_clouds(options.snow_from_clouds)
+        .with_ground_snow(options.ground_snow)
+        .with_drift(options.drift)
+        .with_reflections(options.reflections);
+    if let Some(seed) = options.snow_seed() {
+        snow_frame = snow_frame.with_seed(seed);
     }
-}
 
-impl StringWidth for ColoredString {
-    fn width(&self) -> usize {
-        return self.input.width();
+    let mut christmas_tree_frame = ChristmasTreeFrame::default()
+        .with_ornament_density(options.ornament_density)
+        .unwrap_or_else(|message| invalid(&message))
+        .with_shape(options.tree_shape)
+        .with_snow_caps(options.snow_caps)
+        .with_smooth(options.smooth_tree)
+        .with_reflections(options.reflections)
+        .with_fill(options.layout == Layout::Fullscreen)
+        .with_shrink(options.autoscale)
+        .with_blessing_template(blessing_template(&options.lang).unwrap())
+        .with_blessing_templates(&options.messages)
+        .with_blessing_interval(Duration::from_secs_f32(options.message_interval))
+        .unwrap_or_else(|message| invalid(&message))
+        .with_blessing_fade(options.message_fade)
+        .with_blessing_shown(options.blessing)
+        .with_blessing_align(options.blessing_align, options.blessing_offset)
+        .with_typewriter(options.typewriter);
+    // a dry run leaves the day of the last run alone
+    let first_snow = options.first_snow
+        && !options.dry_run
+        && options
+            .first_snow_state()
+            .is_some_and(|path| first_snow::is_first_run(&path, Date::today()));
+    if first_snow {
+        christmas_tree_frame =
+            christmas_tree_frame.with_blessing_templates(&[first_snow::FIRST_SNOW.to_owned()]);
     }
-}
-
-#[derive(Clone)]
-enum Content {
-    Transparent,
-    ColoredString { s: ColoredString },
-    Compensate,
-}
-
-trait Frame {
-    fn update(&mut self, screen_width: usize, screen_height: usize);
-    fn get_content(&mut self, x: usize, y: usize) -> Content;
-}
-
-struct SnowFrame {
-    thread_rng: ThreadRng,
-    frame_width: usize,
-    frame_height: usize,
-    cursor: usize,
-    snows_row: Vec<BitSet>,
-}
-
-impl Default for SnowFrame {
-    fn default() -> Self {
-        return SnowFrame {
-            thread_rng: thread_rng(),
-            frame_width: 0,
-            frame_height: 0,
-            cursor: 0,
-            snows_row: Vec::new(),
-        };
+    if options.lights_charge {
+        christmas_tree_frame = christmas_tree_frame
+            .with_lights_charge(Duration::from_secs_f32(options.lights_charge_seconds))
+            .unwrap_or_else(|message| invalid(&message));
+    }
+    if let Some(year) = options.year {
+        christmas_tree_frame = christmas_tree_frame.with_year(year);
+    }
+    if let Some(seed) = options.tree_seed() {
+        christmas_tree_frame = christmas_tree_frame
+            .with_seed(seed)
+            .with_twinkle(!options.tree_seeded_by_date());
     }
-}
 
-impl Frame for SnowFrame {
-    fn update(&mut self, screen_width: usize, screen_height: usize) {
-        if self.frame_width != screen_width || self.frame_height != screen_height {
-            self.frame_width = screen_width;
-            self.frame_height = screen_height;
-            self.cursor = 0;
-            self.snows_row = vec![BitSet::with_capacity(screen_width); screen_height];
-        } else {
-            self.cursor = (self.cursor + screen_height - 1) % screen_height;
+    let mut frames: Vec<Box<dyn Frame>> = Vec::new();
+    // names of the layers in frames, for a dry run to list and controls to show or hide
+    let mut layers = Vec::new();
+    if options.debug {
+        frames.push(Box::<DebugFrame>::default());
+        layers.push("debug".to_owned());
+    }
+    if options.window {
+        let window_frame = WindowFrame::default()
+            .with_ledge_height(options.window_ledge)
+            .unwrap_or_else(|message| invalid(&message));
+        frames.push(Box::new(window_frame));
+        layers.push("window".to_owned());
+    }
+    if options.to.is_some() || options.from.is_some() {
+        let mut greeting_frame = GreetingFrame::default();
+        if let Some(to) = &options.to {
+            greeting_frame = greeting_frame.with_to(to);
         }
-
-        let snows = &mut self.snows_row[self.cursor];
-        snows.clear();
-
-        for i in 0..self.frame_width {
-            if self.thread_rng.gen_range(0..=20) == 0 {
-                snows.insert(i);
+        if let Some(from) = &options.from {
+            greeting_frame = greeting_frame.with_from(from);
+        }
+        frames.push(Box::new(greeting_frame));
+        layers.push("greeting".to_owned());
+    }
+    let near_snow_frame = options.layered_snow.then(|| {
+        let speed = options.snow_speed;
+        let mut near_snow_frame = SnowFrame::default()
+            .with_density(NEAR_SNOW_DENSITY)
+            .and_then(|frame| {
+                return frame
+                    .with_fall_speed(speed.min * NEAR_SNOW_SPEEDUP, speed.max * NEAR_SNOW_SPEEDUP);
+            })
+            .unwrap_or_else(|message| invalid(&message))
+            .with_prefill(options.snow_prefill)
+            .with_sparkle(options.sparkle)
+            .with_clouds(options.snow_from_clouds)
+            .with_settling(false);
+        if let Some(seed) = options.near_snow_seed() {
+            near_snow_frame = near_snow_frame.with_seed(seed);
+        }
+        return near_snow_frame;
+    });
+    let mut push = |layer: &str, frame: Box<dyn Frame>| {
+        layers.push(layer.to_owned());
+        match options.parallax(layer) {
+            Some(parallax) => match ParallaxFrame::new(frame, parallax) {
+                Ok(frame) => frames.push(Box::new(frame)),
+                Err(message) => invalid(&format!("{} layer: {}", layer, message)),
+            },
+            None => frames.push(frame),
+        }
+    };
+    if let Some(near_snow_frame) = near_snow_frame {
+        push("snow", Box::new(near_snow_frame));
+    }
+    match options.scene.unwrap_or(SceneKind::Christmas) {
+        SceneKind::Christmas => push("tree", Box::new(christmas_tree_frame)),
+        SceneKind::NewYearCountdown => {
+            let year = options.year.unwrap_or(Date::today().year + 1);
+            let banner = BannerFrame::new(&format!("Happy New Year {}", year));
+            push("banner", Box::new(banner));
+            let countdown = Duration::from_secs(COUNTDOWN_SECONDS);
+            push("countdown", Box::new(CountdownFrame::new(countdown)));
+            let mut fireworks_frame = FireworksFrame::default();
+            if let Some(seed) = options.fireworks_seed() {
+                fireworks_frame = fireworks_frame.with_seed(seed);
             }
+            push("fireworks", Box::new(fireworks_frame));
+        }
+    }
+    if options.garland {
+        push("garland", Box::<GarlandFrame>::default());
+    }
+    if options.sleigh {
+        push(
+            "sleigh",
+            Box::new(
+                SantaSleighFrame::default()
+                    .with_interval(options.sleigh_interval)
+                    .with_bubble(options.sleigh_bubble),
+            ),
+        );
+    }
+    if options.candles {
+        let mut candles_frame = CandlesFrame::default();
+        if let Some(seed) = options.candles_seed() {
+            candles_frame = candles_frame.with_seed(seed);
+        }
+        push("candles", Box::new(candles_frame));
+    }
+    if options.gifts {
+        let mut gifts_frame = GiftsFrame::default();
+        if let Some(seed) = options.gifts_seed() {
+            gifts_frame = gifts_frame.with_seed(seed);
+        }
+        push("gifts", Box::new(gifts_frame));
+    }
+    if options.snowman {
+        let mut snowman_frame = SnowmanFrame::default().with_melt_reset(options.snowman_reset);
+        if let Some(minutes) = options.snowman_melt {
+            snowman_frame = snowman_frame
+                .with_melt(Duration::from_secs_f32(minutes * 60.0))
+                .unwrap_or_else(|message| invalid(&message));
+        }
+        push("snowman", Box::new(snowman_frame));
+    }
+    for name in &options.extra_frames {
+        push(name, create_frame(name).unwrap());
+    }
+    if options.clouds {
+        let mut clouds_frame = CloudsFrame::default();
+        if let Some(seed) = options.clouds_seed() {
+            clouds_frame = clouds_frame.with_seed(seed);
+        }
+        push("clouds", Box::new(clouds_frame));
+    }
+    push("snow", Box::new(snow_frame));
+    for depth in 1..options.snow_layers {
+        push("snow", Box::new(far_snow(&options, depth)));
+    }
+    if options.aurora {
+        push(
+            "aurora",
+            Box::new(AuroraFrame::default().with_wind(options.aurora_wind)),
+        );
+    }
+    let mut printer = Printer::new(frames);
+    printer.scene_mut().wind = options.wind;
+    printer.scene_mut().scroll_speed = options.scroll_speed;
+    printer.scene_mut().pan_speed = options.pan * options.pan_direction.sign();
+    printer.scene_mut().bpm = options.bpm;
+    printer.scene_mut().virtual_width = options.world_width.unwrap_or(0);
+    printer.set_vignette(options.vignette);
+    printer.set_reversed(options.reverse_layers);
+    printer.set_debug_overlap(options.debug_overlap);
+    printer.set_catch_panics(options.catch_panics);
+    printer.set_fill(
+        options.fill_char.0,
+        options.fill_color.map(|fill_color| fill_color.0),
+    );
+    match options.layout {
+        Layout::Auto => printer.set_max_width(options.max_width),
+        Layout::Fullscreen => {}
+        Layout::Compact => {
+            let (width, height) = COMPACT_SIZE;
+            printer.set_max_width(Some(options.max_width.map_or(width, |max| max.min(width))));
+            printer.set_max_height(Some(height));
         }
     }
 
-    fn get_content(&mut self, x: usize, y: usize) -> Content {
-        let y = (self.cursor + y) % self.frame_height;
-        if self.snows_row[y].contains(x) {
-            return Content::ColoredString { s: "o".white() };
-        } else {
-            return Content::Transparent;
+    if let Some(path) = &options.save_config {
+        if let Err(message) = config::save(path, &options) {
+            eprintln!("error: {}", message);
+            process::exit(1);
         }
+        println!("saved the options to '{}'", path);
+        return;
+    }
+    if options.dry_run {
+        if let Some(path) = &options.replay {
+            if let Err(message) = Recording::load(path) {
+                eprintln!("error: {}", message);
+                process::exit(1);
+            }
+        }
+        println!("ok: {} layers built, {}", layers.len(), layers.join(", "));
+        return;
     }
-}
 
-struct ChristmasTreeFrame {
-    thread_rng: ThreadRng,
-    frame_width: usize,
-    frame_height: usize,
-}
+    #[cfg(feature = "image")]
+    if let Some(dir) = &options.frames_dir {
+        if let Err(error) = export(&mut printer, &options, dir) {
+            eprintln!("error: cannot write frames to '{}': {}", dir, error);
+            process::exit(1);
+        }
+        return;
+    }
 
-impl ChristmasTreeFrame {
-    fn get_leaf_color(&mut self) -> Color {
-        return match self.thread_rng.gen_range(0..=5) {
-            0 => Color::Red,
-            1 => Color::Green,
-            2 => Color::Yellow,
-            3 => Color::Blue,
-            4 => Color::Magenta,
-            5 => Color::Cyan,
-            _ => Color::White,
-        };
+    let recording = match (&options.replay, options.precompute) {
+        (Some(path), _) => match Recording::load(path) {
+            Ok(recording) => Some(recording),
+            Err(message) => {
+                eprintln!("error: {}", message);
+                process::exit(1);
+            }
+        },
+        (None, Some(count)) => Some(precompute(&mut printer, &options, count)),
+        (None, None) => None,
+    };
+
+    let session = Session::enter(&options.title, options.kiosk, options.focus_reactive);
+    match &recording {
+        Some(recording) => play(recording, &options, &session),
+        None => {
+            let controls = options
+                .control_stdin
+                .then(|| Controls::listen(layers.clone()));
+            run(&mut printer, &options, &session, &layers, controls.as_ref());
+        }
     }
 
-    fn get_leaf(&mut self) -> ColoredString {
-        return match self.thread_rng.gen_range(0..=10) {
-            0 => "o".color(self.get_leaf_color()),
-            _ => "*".green(),
-        };
+    // a screensaver gets out of the way at once, and a recording has no picture to fade
+    if options.fade && !options.kiosk && recording.is_none() {
+        fade_out(&mut printer, &options, &session);
+    }
+    match options.region {
+        Some(region) => printer.erase_at(region.x, region.y),
+        None => clear(&printer),
     }
 }
 
-impl Default for ChristmasTreeFrame {
-    fn default() -> Self {
-        return ChristmasTreeFrame {
-            thread_rng: thread_rng(),
-            frame_width: 0,
-            frame_height: 0,
-        };
+/// The `depth`th of the --snow-layers behind the first, which is the usual
+/// snow. Going back from the first to the furthest, the speed, brightness and
+/// density of the flakes drop linearly to FAR_SNOW_SPEED, FAR_SNOW_BRIGHTNESS
+/// and FAR_SNOW_DENSITY times the first's, and the back half of the layers
+/// draw their flakes as dots. Only the first lets snow settle or pile up.
+fn far_snow(options: &Options, depth: usize) -> SnowFrame {
+    let distance = depth as f32 / (options.snow_layers - 1) as f32;
+    let ease = |far: f32| 1.0 - (1.0 - far) * distance;
+    let speed = options.snow_speed;
+    // never slower than flakes can fall
+    let (min, max) = (
+        (speed.min * ease(FAR_SNOW_SPEED)).max(0.1),
+        (speed.max * ease(FAR_SNOW_SPEED)).max(0.1),
+    );
+    let mut far_snow_frame = SnowFrame::default()
+        .with_fall_speed(min, max)
+        .and_then(|frame| frame.with_brightness(ease(FAR_SNOW_BRIGHTNESS)))
+        .and_then(|frame| frame.with_density(ease(FAR_SNOW_DENSITY)))
+        .unwrap_or_else(|message| invalid(&message))
+        .with_glyph(if distance > 0.5 { '.' } else { 'o' })
+        .with_prefill(options.snow_prefill)
+        .with_sparkle(options.sparkle)
+        .with_clouds(options.snow_from_clouds)
+        .with_settling(false);
+    if let Some(seed) = options.far_snow_seed(depth) {
+        far_snow_frame = far_snow_frame.with_seed(seed);
     }
+
+    return far_snow_frame;
 }
 
-impl Frame for ChristmasTreeFrame {
-    fn update(&mut self, screen_width: usize, screen_height: usize) {
-        if self.frame_width != screen_width || self.frame_height != screen_height {
-            self.frame_width = screen_width;
-            self.frame_height = screen_height;
-        }
+// reports options the frames refused, like a command line error
+fn invalid(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    process::exit(2);
+}
+
+fn run(
+    printer: &mut Printer,
+    options: &Options,
+    session: &Session,
+    layers: &[String],
+    controls: Option<&Controls>,
+) {
+    // overwriting leaves whatever was on the screen before around the picture
+    if options.region.is_none() && options.render_mode == RenderMode::Overwrite {
+        clear(printer);
     }
 
-    fn get_content(&mut self, x: usize, y: usize) -> Content {
-        const HEIGHT: usize = 14;
-        // christmas tree only shows on the middle of 14 rows
-        let y_offset = (self.frame_height - HEIGHT) / 2;
-        if y < y_offset || y >= y_offset + HEIGHT {
-            return Content::Transparent;
+    let mut budget = options
+        .max_frame_ms
+        .map(|ms| FrameBudget::new(Duration::from_millis(ms), options.downgrade.0.clone()));
+    let mut frame_count = 0;
+    loop {
+        let started = Instant::now();
+        advance(printer, options);
+        draw(printer, options);
+        frame_count += 1;
+        if let Some(adjustment) = budget
+            .as_mut()
+            .and_then(|budget| budget.record(started.elapsed()))
+        {
+            adjust(printer, options, layers, adjustment);
         }
 
-        // leaf part
-        const LEAF_HEIGHT: usize = 10;
-        if y - y_offset < LEAF_HEIGHT {
-            let leaf_width = 2 * (y - y_offset) + 1;
-            let leaf_offset = (self.frame_width - leaf_width) / 2;
-            if x < leaf_offset || x >= leaf_offset + leaf_width {
-                return Content::Transparent;
-            } else {
-                return Content::ColoredString { s: self.get_leaf() };
-            }
+        let quit = session.wait_for_quit(Duration::from_millis(options.interval));
+        if quit || options.frames.is_some_and(|frames| frame_count >= frames) {
+            break;
         }
-
-        // trunk part
-        const TRUNK_HEIGHT: usize = 2;
-        if y - y_offset - LEAF_HEIGHT < TRUNK_HEIGHT {
-            let trunk = "mWm".to_owned();
-            let trunk_vec = string_to_content_vec(&trunk, BROWN);
-            let trunk_width = 3;
-            let trunk_offset = (self.frame_width - trunk_width) / 2;
-            if x < trunk_offset || x >= trunk_offset + trunk_width {
-                return Content::Transparent;
-            } else {
-                return trunk_vec[x - trunk_offset].clone();
-            }
+        if options.focus_reactive {
+            printer.scene_mut().snowfall = session.focused().map(|focused| {
+                return if focused {
+                    FOCUSED_SNOWFALL
+                } else {
+                    UNFOCUSED_SNOWFALL
+                };
+            });
         }
-
-        // blank part
-        const BLANK_HEIGHT: usize = 1;
-        if y - y_offset - LEAF_HEIGHT - TRUNK_HEIGHT < BLANK_HEIGHT {
-            return Content::Transparent;
+        if let Some(controls) = controls {
+            controls.apply_pending(printer);
         }
+    }
+}
 
-        // blessing part
-        const BLESSING_HEIGHT: usize = 1;
-        if y - y_offset - LEAF_HEIGHT - TRUNK_HEIGHT - BLANK_HEIGHT < BLESSING_HEIGHT {
-            let blessing = "2024 聖誕快樂".to_owned();
-            let blessing_vec = string_to_content_vec(&blessing, Color::Red);
-            let blessing_width = blessing.width();
-            let blessing_offset = (self.frame_width - blessing_width) / 2;
-            if x < blessing_offset || x >= blessing_offset + blessing_width {
-                return Content::Transparent;
-            } else {
-                return blessing_vec[x - blessing_offset].clone();
+// takes or undoes a downgrade of --max-frame-ms on the card of the `layers` named
+fn adjust(printer: &mut Printer, options: &Options, layers: &[String], adjustment: Adjustment) {
+    let (downgrade, taken) = match adjustment {
+        Adjustment::Take(downgrade) => (downgrade, true),
+        Adjustment::Undo(downgrade) => (downgrade, false),
+    };
+    match downgrade {
+        Downgrade::Layer(name) => {
+            for (i, layer) in layers.iter().enumerate() {
+                if *layer == name {
+                    printer.set_hidden(i, taken);
+                }
             }
         }
-
-        return Content::Transparent;
+        Downgrade::Vignette => printer.set_vignette(if taken { 0.0 } else { options.vignette }),
+        Downgrade::Snowfall => {
+            let scene = printer.scene_mut();
+            // scaling whichever snowfall --focus-reactive or a control sets
+            let scale = scene.snowfall_scale.unwrap_or(1.0);
+            scene.snowfall_scale = Some(if taken {
+                scale * DOWNGRADED_SNOWFALL
+            } else {
+                scale / DOWNGRADED_SNOWFALL
+            });
+        }
     }
 }
 
-struct Printer {
-    screen_width: usize,
-    screen_height: usize,
-    frames: Vec<Box<dyn Frame>>,
+fn advance(printer: &mut Printer, options: &Options) {
+    match (options.region, options.size) {
+        (Some(region), _) => printer.update_with_size(region.width, region.height),
+        (None, Some(size)) => printer.update_with_size(size.width, size.height),
+        (None, None) => printer.update(),
+    }
+    printer.scene_mut().time += Duration::from_millis(options.interval);
 }
 
-impl Printer {
-    fn new(frames: Vec<Box<dyn Frame>>) -> Self {
-        return Printer {
-            screen_width: 0,
-            screen_height: 0,
-            frames,
-        };
-    }
+/// Writes every frame to a numbered image in `dir` rather than the terminal.
+#[cfg(feature = "image")]
+fn export(printer: &mut Printer, options: &Options, dir: &str) -> io::Result<()> {
+    use std::{fs, io::BufWriter, path::Path};
 
-    fn update(&mut self) {
-        let (screen_width, screen_height) = term_size::dimensions().unwrap();
-        self.screen_width = screen_width;
-        self.screen_height = screen_height;
+    fs::create_dir_all(dir)?;
+    for frame in 1..=options.frames.unwrap_or(0) {
+        advance(printer, options);
+        let image = cyber_christmas_card::rasterize(&printer.render_cells());
 
-        for frame in self.frames.iter_mut() {
-            frame.update(screen_width, screen_height);
+        let extension = match options.frames_format {
+            cli::ImageFormat::Png => "png",
+            cli::ImageFormat::Ppm => "ppm",
+        };
+        let name = format!("frame-{:05}.{}", frame, extension);
+        let mut out = BufWriter::new(fs::File::create(Path::new(dir).join(name))?);
+        match options.frames_format {
+            cli::ImageFormat::Png => image.write_png(&mut out)?,
+            cli::ImageFormat::Ppm => image.write_ppm(&mut out)?,
         }
+        out.flush()?;
     }
 
-    fn clear(&self) {
-        if cfg!(target_os = "windows") {
-            Command::new("cmd").args(&["/C", "cls"]).status().unwrap();
-        } else {
-            Command::new("clear").status().unwrap();
-        }
-    }
+    return Ok(());
+}
 
-    fn print(&mut self) {
-        let contents = (0..self.screen_height)
-            .into_iter()
-            .map(|y| {
-                let mut row_strings = String::new();
-
-                let mut x = 0;
-                while x < self.screen_width {
-                    let content = self
-                        .frames
-                        .iter_mut()
-                        .map(|frame| frame.get_content(x, y))
-                        .find(|content| match content {
-                            Content::Transparent | Content::Compensate => false,
-                            _ => true,
-                        });
-
-                    match content {
-                        Some(Content::ColoredString { s }) => {
-                            row_strings.push_str(&s.to_string());
-                            x += s.width() - 1;
-                        }
-                        _ => {
-                            row_strings.push(' ');
-                        }
-                    }
-
-                    x += 1;
+/// Renders `count` frames up front, sized as they would be when shown.
+fn precompute(printer: &mut Printer, options: &Options, count: usize) -> Recording {
+    let delay = Duration::from_millis(options.interval);
+    let frames = (0..count)
+        .map(|_| {
+            advance(printer, options);
+            let mut output = Vec::new();
+            match options.region {
+                Some(region) => printer.write_at(&mut output, region.x, region.y).unwrap(),
+                None => {
+                    printer.clear_to(&mut output).unwrap();
+                    printer.write_to(&mut output).unwrap();
                 }
+            }
+            return (output, delay);
+        })
+        .collect();
 
-                return row_strings;
-            })
-            .collect::<Vec<String>>()
-            .join("\n");
+    return Recording { frames };
+}
 
-        print!("{}", contents);
+/// Shows the frames of `recording` over and over until asked to quit.
+fn play(recording: &Recording, options: &Options, session: &Session) {
+    if recording.frames.is_empty() {
+        return;
     }
-}
 
-fn string_to_content_vec(s: &str, color: Color) -> Vec<Content> {
-    let string_width = s.to_owned().width();
-    let mut content_vec = Vec::<Content>::with_capacity(string_width);
+    let mut stdout = io::stdout();
+    for (frame_count, (output, delay)) in (1..).zip(recording.frames.iter().cycle()) {
+        stdout.write_all(output).unwrap();
+        stdout.flush().unwrap();
 
-    s.chars().into_iter().for_each(|c| {
-        content_vec.push(Content::ColoredString {
-            s: c.to_string().color(color),
-        });
+        let quit = session.wait_for_quit(*delay);
+        if quit || options.frames.is_some_and(|frames| frame_count >= frames) {
+            break;
+        }
+    }
+}
 
-        if !c.is_ascii() {
-            content_vec.push(Content::Compensate);
+fn draw(printer: &mut Printer, options: &Options) {
+    match (options.region, options.render_mode) {
+        (Some(region), RenderMode::Diff) => printer
+            .write_changes_at(&mut io::stdout(), region.x, region.y, false)
+            .unwrap(),
+        (Some(region), _) => printer.print_at(region.x, region.y),
+        (None, RenderMode::Clear) => {
+            clear(printer);
+            printer.print();
         }
-    });
+        (None, RenderMode::Overwrite) => {
+            print!("\x1b[H");
+            printer.print();
+        }
+        (None, RenderMode::Diff) => printer
+            .write_changes_at(&mut io::stdout(), 0, 0, true)
+            .unwrap(),
+    }
+}
 
-    return content_vec;
+fn clear(printer: &Printer) {
+    // there is no terminal for the `clear` command to clear when piped, and
+    // on Windows escape codes spare the console the flicker of `cls` if it supports them
+    if io::stdout().is_terminal() && !(cfg!(windows) && terminal::ansi_supported()) {
+        printer.clear();
+    } else {
+        printer.clear_to(&mut io::stdout()).unwrap();
+    }
 }
 
-fn main() {
-    let snow_frame = Box::new(SnowFrame::default());
-    let christmas_tree_frame = Box::new(ChristmasTreeFrame::default());
-    let mut printer = Printer::new(vec![christmas_tree_frame, snow_frame]);
+/// Dims the last picture to black, skipped by pressing quit again.
+fn fade_out(printer: &mut Printer, options: &Options, session: &Session) {
+    for step in 1..=FADE_STEPS {
+        printer.set_brightness(1.0 - step as f32 / FADE_STEPS as f32);
+        draw(printer, options);
 
-    loop {
-        printer.update();
-        printer.clear();
-        printer.print();
-        thread::sleep(Duration::from_millis(1000));
+        if session.wait_for_quit(FADE_DURATION / FADE_STEPS) {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thins_whichever_snowfall_is_set_and_gives_it_back_whole() {
+        let mut printer = Printer::new(Vec::new());
+        let options = Options::default();
+        let snowfall = Downgrade::Snowfall;
+        adjust(
+            &mut printer,
+            &options,
+            &[],
+            Adjustment::Take(snowfall.clone()),
+        );
+        assert_eq!(printer.scene_mut().scaled_snowfall(), DOWNGRADED_SNOWFALL);
+
+        // the terminal gaining focus while thinned
+        printer.scene_mut().snowfall = Some(FOCUSED_SNOWFALL);
+        assert_eq!(
+            printer.scene_mut().scaled_snowfall(),
+            FOCUSED_SNOWFALL * DOWNGRADED_SNOWFALL
+        );
+
+        // then a control going back to the usual snowfall before the undo
+        printer.scene_mut().snowfall = None;
+        adjust(&mut printer, &options, &[], Adjustment::Undo(snowfall));
+        let scene = printer.scene_mut();
+        assert_eq!(scene.snowfall, None);
+        assert_eq!(scene.scaled_snowfall(), 1.0);
     }
 }
@@ -0,0 +1,860 @@
+use std::{
+    io::{self, Write},
+    panic::{self, AssertUnwindSafe},
+    process::Command,
+};
+
+use colored::{Color, ColoredString, Colorize};
+
+use crate::{
+    color,
+    frame::{Bounds, Content, Frame},
+    scene::Scene,
+    string_width::StringWidth,
+};
+
+/// A composited cell of the final picture.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Cell {
+    /// No frame drew here; rendered as a space.
+    Empty,
+    Glyph {
+        s: ColoredString,
+    },
+    /// Second column of the wide glyph to its left. No output draws anything
+    /// here, the glyph already taking up the column: lines simply leave it
+    /// out, `write_changes_at` never positions the cursor on it alone,
+    /// rasterized pictures draw the glyph across it and `CardWidget` resets
+    /// it for ratatui to skip. A wide glyph is only ever shown where both of
+    /// its columns fit in the picture.
+    Continuation,
+}
+
+/// Composites a stack of frames, the first frame being the top-most layer.
+///
+/// Each cell shows the first frame, in stacking order, that draws anything
+/// there; transparent cells and the second columns of wide glyphs let the
+/// frames below show through. Stacking order is the order of `frames`,
+/// or the reverse of it after `set_reversed(true)`. It only decides what is
+/// shown: solids, surfaces, lights and events are gathered from every frame
+/// either way.
+///
+/// `print` draws straight to the terminal. Embedders that own the screen
+/// themselves (e.g. a TUI drawing the card as a widget) should instead size
+/// the printer to their region with `update_with_size(width, height)` and
+/// take the picture from `render_lines` or `render_cells`. Row `i` of the
+/// result belongs at row `region.y + i` starting from column `region.x`.
+pub struct Printer {
+    screen_width: usize,
+    screen_height: usize,
+    frames: Vec<Box<dyn Frame>>,
+    // frames left out for the rest of the run after panicking
+    panicked: Vec<bool>,
+    // frames kept running but left out of the picture
+    hidden: Vec<bool>,
+    catch_panics: bool,
+    // the last frame being the top-most layer instead of the first
+    reversed: bool,
+    // cells more than one frame draws at highlighted
+    debug_overlap: bool,
+    fill: char,
+    fill_color: Option<Color>,
+    scene: Scene,
+    brightness: f32,
+    vignette: f32,
+    max_width: Option<usize>,
+    max_height: Option<usize>,
+    // blank columns left of and rows above the scene when it is smaller than its region
+    margin_x: usize,
+    margin_y: usize,
+    // styled cells of the picture last drawn by `write_changes_at`, with its margins
+    drawn: Vec<Vec<Option<ColoredString>>>,
+    drawn_margins: (usize, usize),
+}
+
+impl Printer {
+    pub fn new(frames: Vec<Box<dyn Frame>>) -> Self {
+        return Printer {
+            screen_width: 0,
+            screen_height: 0,
+            panicked: vec![false; frames.len()],
+            hidden: vec![false; frames.len()],
+            catch_panics: true,
+            reversed: false,
+            debug_overlap: false,
+            fill: ' ',
+            fill_color: None,
+            frames,
+            scene: Scene::default(),
+            brightness: 1.0,
+            vignette: 0.0,
+            max_width: None,
+            max_height: None,
+            margin_x: 0,
+            margin_y: 0,
+            drawn: Vec::new(),
+            drawn_margins: (0, 0),
+        };
+    }
+
+    /// Advances every frame, sized to the whole terminal, or to 80 x 24 when
+    /// there is no terminal to measure.
+    pub fn update(&mut self) {
+        let (screen_width, screen_height) = term_size::dimensions().unwrap_or((80, 24));
+        self.update_with_size(screen_width, screen_height);
+    }
+
+    /// Advances every frame, sized to a `width` x `height` region.
+    pub fn update_with_size(&mut self, screen_width: usize, screen_height: usize) {
+        let (region_width, region_height) = (screen_width, screen_height);
+        let screen_width = self
+            .max_width
+            .map_or(screen_width, |max| screen_width.min(max));
+        let screen_height = self
+            .max_height
+            .map_or(screen_height, |max| screen_height.min(max));
+        self.screen_width = screen_width;
+        self.screen_height = screen_height;
+        self.margin_x = (region_width - screen_width) / 2;
+        self.margin_y = (region_height - screen_height) / 2;
+
+        let catch_panics = self.catch_panics;
+        for (i, (frame, panicked)) in self.frames.iter_mut().zip(&mut self.panicked).enumerate() {
+            guard(catch_panics, i, panicked, || {
+                frame.update(screen_width, screen_height)
+            });
+        }
+
+        self.scene.reset(screen_width, screen_height);
+        self.scene.scroll += self.scene.scroll_speed;
+        self.scene.camera += self.scene.pan_speed;
+        for y in 0..screen_height {
+            for x in 0..screen_width {
+                let solid = self.frames.iter().zip(&mut self.panicked).enumerate().any(
+                    |(i, (frame, panicked))| {
+                        !self.hidden[i]
+                            && guard(catch_panics, i, panicked, || frame.is_solid(x, y))
+                                .unwrap_or(false)
+                    },
+                );
+                if solid {
+                    self.scene.set_solid(x, y);
+                }
+            }
+        }
+        for (i, (frame, panicked)) in self.frames.iter().zip(&mut self.panicked).enumerate() {
+            let reported = guard(catch_panics, i, panicked, || {
+                return (
+                    frame.solid_surfaces(),
+                    frame.snow_ledges(),
+                    frame.clouds(),
+                    frame.light_sources(),
+                    frame.snow_depths(),
+                    frame.events(),
+                );
+            });
+            let Some((surfaces, ledges, clouds, lights, snow_depths, events)) = reported else {
+                continue;
+            };
+            for event in events {
+                self.scene.add_event(event);
+            }
+            if self.hidden[i] {
+                continue;
+            }
+            for surface in surfaces {
+                self.scene.add_surface(&surface);
+            }
+            for ledge in ledges {
+                self.scene.add_ledge(&ledge);
+            }
+            for columns in clouds {
+                self.scene.add_cloud(columns);
+            }
+            for light in lights {
+                self.scene.add_light(light);
+            }
+            self.scene.add_snow_depths(&snow_depths);
+        }
+
+        let scene = &self.scene;
+        for (i, (frame, panicked)) in self.frames.iter_mut().zip(&mut self.panicked).enumerate() {
+            guard(catch_panics, i, panicked, || frame.tick(scene));
+        }
+    }
+
+    /// Caps the width of the scene. A wider region keeps the scene centred
+    /// between blank margins, which `print`, `print_at` and `erase_at` take
+    /// care of; `render_lines` and `render_cells` cover the scene alone.
+    pub fn set_max_width(&mut self, max_width: Option<usize>) {
+        self.max_width = max_width;
+    }
+
+    /// Caps the height of the scene, centring it like `set_max_width` does.
+    pub fn set_max_height(&mut self, max_height: Option<usize>) {
+        self.max_height = max_height;
+    }
+
+    /// Sets what `render_lines` draws where no frame drew anything, a space
+    /// in the terminal's own colors by default. `fill` should be a single
+    /// column wide.
+    pub fn set_fill(&mut self, fill: char, fill_color: Option<Color>) {
+        self.fill = fill;
+        self.fill_color = fill_color;
+    }
+
+    /// Whether a panicking frame is left out for the rest of the run, with a
+    /// warning on stderr, rather than bringing the whole card down. On by
+    /// default; turn it off to debug a frame.
+    pub fn set_catch_panics(&mut self, catch_panics: bool) {
+        self.catch_panics = catch_panics;
+    }
+
+    /// Stacks the frames the other way up, the last frame becoming the
+    /// top-most layer, for trying out another layer order without rebuilding
+    /// the stack. Off by default.
+    pub fn set_reversed(&mut self, reversed: bool) {
+        self.reversed = reversed;
+    }
+
+    /// Highlights in magenta every cell that more than one frame draws
+    /// something at, the top-most of them still showing, for seeing where
+    /// layers collide. Off by default, as every frame is then asked about
+    /// every cell rather than just down to the first showing something.
+    pub fn set_debug_overlap(&mut self, debug_overlap: bool) {
+        self.debug_overlap = debug_overlap;
+    }
+
+    /// Leaves the `i`th frame out of the picture while it keeps running, as
+    /// well as the solids, surfaces, lights and snow it reports. The events it
+    /// reports still go around. Frames past the last are left alone.
+    pub fn set_hidden(&mut self, i: usize, hidden: bool) {
+        if let Some(frame_hidden) = self.hidden.get_mut(i) {
+            *frame_hidden = hidden;
+        }
+    }
+
+    pub fn scene_mut(&mut self) -> &mut Scene {
+        return &mut self.scene;
+    }
+
+    /// Dims the whole picture, `1.0` being untouched and `0.0` black.
+    pub fn set_brightness(&mut self, brightness: f32) {
+        self.brightness = brightness.clamp(0.0, 1.0);
+    }
+
+    /// Darkens cells away from the lights of the scene, `0.0` being off and
+    /// `1.0` leaving unlit cells black. Without any lights nothing changes.
+    pub fn set_vignette(&mut self, vignette: f32) {
+        self.vignette = vignette.clamp(0.0, 1.0);
+    }
+
+    fn brightness_at(&self, x: usize, y: usize) -> f32 {
+        let lights = self.scene.lights();
+        if self.vignette == 0.0 || lights.is_empty() {
+            return self.brightness;
+        }
+
+        let lit = lights
+            .iter()
+            .map(|light| light.intensity_at(x, y))
+            .fold(0.0, f32::max);
+        return self.brightness * (1.0 - self.vignette * (1.0 - lit));
+    }
+
+    pub fn clear(&self) {
+        if cfg!(target_os = "windows") {
+            Command::new("cmd").args(["/C", "cls"]).status().unwrap();
+        } else {
+            Command::new("clear").status().unwrap();
+        }
+    }
+
+    /// Clears the screen with escape codes rather than the `clear` command,
+    /// for output that is not going straight to a terminal.
+    pub fn clear_to(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "\x1b[H\x1b[2J")?;
+        return out.flush();
+    }
+
+    pub fn print(&mut self) {
+        self.write_to(&mut io::stdout()).unwrap();
+    }
+
+    pub fn write_to(&mut self, out: &mut impl Write) -> io::Result<()> {
+        // carriage returns keep the rows aligned when the terminal is in raw mode
+        let margin = " ".repeat(self.margin_x);
+        let top = "\r\n".repeat(self.margin_y);
+        let lines = self
+            .render_lines()
+            .iter()
+            .map(|line| format!("{}{}", margin, line))
+            .collect::<Vec<String>>();
+        write!(out, "{}{}", top, lines.join("\r\n"))?;
+        return out.flush();
+    }
+
+    /// Draws the picture with its top left corner at column `x` and row `y`,
+    /// leaving the rest of the terminal untouched.
+    pub fn print_at(&mut self, x: usize, y: usize) {
+        self.write_at(&mut io::stdout(), x, y).unwrap();
+    }
+
+    pub fn write_at(&mut self, out: &mut impl Write, x: usize, y: usize) -> io::Result<()> {
+        let (x, y) = (x + self.margin_x, y + self.margin_y);
+        let mut output = String::new();
+        for (i, line) in self.render_lines().iter().enumerate() {
+            output.push_str(&format!("\x1b[{};{}H{}", y + i + 1, x + 1, line));
+        }
+        write!(out, "{}", output)?;
+        return out.flush();
+    }
+
+    /// Draws only the cells that changed since the last call, with the top
+    /// left corner of the picture at column `x` and row `y`. The first call,
+    /// and any after the picture was resized, draws every cell, having
+    /// cleared the screen first if `clear` is set.
+    pub fn write_changes_at(
+        &mut self,
+        out: &mut impl Write,
+        x: usize,
+        y: usize,
+        clear: bool,
+    ) -> io::Result<()> {
+        let cells = self.render_styled_cells();
+        let margins = (self.margin_x, self.margin_y);
+        let redraw = self.drawn_margins != margins
+            || self.drawn.len() != cells.len()
+            || self.drawn.first().map(Vec::len) != cells.first().map(Vec::len);
+        let (x, y) = (x + self.margin_x, y + self.margin_y);
+
+        let mut output = String::new();
+        if redraw && clear {
+            output.push_str("\x1b[H\x1b[2J");
+        }
+        for (row, row_cells) in cells.iter().enumerate() {
+            let mut changed = (0..row_cells.len())
+                .map(|column| redraw || row_cells[column] != self.drawn[row][column])
+                .collect::<Vec<bool>>();
+            // overwriting half a wide glyph may leave the other half behind
+            for column in 1..row_cells.len() {
+                if changed[column] && !redraw && self.drawn[row][column].is_none() {
+                    changed[column - 1] = true;
+                }
+            }
+
+            let mut column = 0;
+            while column < row_cells.len() {
+                if !changed[column] {
+                    column += 1;
+                    continue;
+                }
+                output.push_str(&format!("\x1b[{};{}H", y + row + 1, x + column + 1));
+                let start = column;
+                while column < row_cells.len() && changed[column] {
+                    column += 1;
+                }
+                output.push_str(&coalesce(&row_cells[start..column]));
+            }
+        }
+
+        self.drawn = cells;
+        self.drawn_margins = margins;
+        write!(out, "{}", output)?;
+        return out.flush();
+    }
+
+    /// Blanks the area `print_at` last drew at column `x` and row `y`.
+    pub fn erase_at(&self, x: usize, y: usize) {
+        let blank = " ".repeat(self.margin_x + self.screen_width);
+        let mut output = String::new();
+        for i in 0..self.margin_y + self.screen_height {
+            output.push_str(&format!("\x1b[{};{}H{}", y + i + 1, x + 1, blank));
+        }
+        print!("{}", output);
+        io::stdout().flush().unwrap();
+    }
+
+    /// The current picture as one styled string per row.
+    pub fn render_lines(&mut self) -> Vec<String> {
+        return self
+            .render_styled_cells()
+            .iter()
+            .map(|row| coalesce(row))
+            .collect();
+    }
+
+    // the current picture as one styled string per cell, none for continuations
+    fn render_styled_cells(&mut self) -> Vec<Vec<Option<ColoredString>>> {
+        return self
+            .render_cells()
+            .iter()
+            .enumerate()
+            .map(|(y, row)| {
+                return row
+                    .iter()
+                    .enumerate()
+                    .map(|(x, cell)| match cell {
+                        Cell::Empty => {
+                            let brightness = self.brightness_at(x, y);
+                            let mut fill = match self.fill_color {
+                                None if self.fill == ' ' || brightness >= 1.0 => {
+                                    return Some(self.fill.to_string().normal());
+                                }
+                                color => self.fill.to_string().color(color.unwrap_or(Color::White)),
+                            };
+                            if brightness < 1.0 {
+                                fill = dim(&fill, brightness);
+                            }
+                            return Some(fill);
+                        }
+                        Cell::Glyph { s } => Some(s.clone()),
+                        Cell::Continuation => None,
+                    })
+                    .collect();
+            })
+            .collect();
+    }
+
+    /// The current picture as a `screen_height` x `screen_width` grid.
+    pub fn render_cells(&mut self) -> Vec<Vec<Cell>> {
+        let mut cells = self.composite();
+        for (y, row) in cells.iter_mut().enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                let brightness = self.brightness_at(x, y);
+                if let (Cell::Glyph { s }, true) = (&cell, brightness < 1.0) {
+                    *cell = Cell::Glyph {
+                        s: dim(s, brightness),
+                    };
+                }
+            }
+        }
+
+        return cells;
+    }
+
+    fn composite(&mut self) -> Vec<Vec<Cell>> {
+        let bounds = self
+            .frames
+            .iter()
+            .zip(&mut self.panicked)
+            .enumerate()
+            .map(|(i, (frame, panicked))| {
+                if self.hidden[i] {
+                    return Some(Bounds::EMPTY);
+                }
+                return guard(self.catch_panics, i, panicked, || frame.content_bounds()).flatten();
+            })
+            .collect::<Vec<Option<Bounds>>>();
+        // indices of the frames, top-most layer first
+        let mut order = (0..self.frames.len()).collect::<Vec<usize>>();
+        if self.reversed {
+            order.reverse();
+        }
+
+        return (0..self.screen_height)
+            .map(|y| {
+                let mut row_cells = vec![Cell::Empty; self.screen_width];
+
+                let mut x = 0;
+                while x < self.screen_width {
+                    let mut drawn = order
+                        .iter()
+                        .filter(|i| bounds[**i].is_none_or(|bounds| bounds.contains(x, y)))
+                        .filter_map(|&i| {
+                            let frame = &mut self.frames[i];
+                            return guard(self.catch_panics, i, &mut self.panicked[i], || {
+                                return frame.get_content(x, y);
+                            });
+                        })
+                        .filter(|content| match content {
+                            Content::Transparent | Content::Compensate => false,
+                            // half a wide glyph in the last column would wrap the line
+                            Content::ColoredString { s } => x + s.width() <= self.screen_width,
+                        });
+                    let content = drawn.next();
+                    let overlapping = self.debug_overlap && drawn.next().is_some();
+
+                    if let Some(Content::ColoredString { mut s }) = content {
+                        if overlapping {
+                            s = s.on_magenta();
+                        }
+                        let width = s.width();
+                        row_cells[x] = Cell::Glyph { s };
+                        for cell in row_cells.iter_mut().take(x + width).skip(x + 1) {
+                            *cell = Cell::Continuation;
+                        }
+                        x += width - 1;
+                    }
+
+                    x += 1;
+                }
+
+                return row_cells;
+            })
+            .collect();
+    }
+}
+
+/// Runs `f` on the `i`th frame unless it panicked before, catching a panic
+/// to leave the frame out from then on.
+fn guard<T>(catch_panics: bool, i: usize, panicked: &mut bool, f: impl FnOnce() -> T) -> Option<T> {
+    if *panicked {
+        return None;
+    }
+    if !catch_panics {
+        return Some(f());
+    }
+
+    return match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(value) => Some(value),
+        Err(_) => {
+            *panicked = true;
+            eprintln!("warning: frame {} panicked and is left out from now on", i);
+            None
+        }
+    };
+}
+
+/// Serializes a run of styled cells, sharing one set of escape codes between
+/// neighbouring cells of the same style rather than repeating them for each.
+fn coalesce(cells: &[Option<ColoredString>]) -> String {
+    let mut output = String::new();
+    let mut span: Option<ColoredString> = None;
+    for cell in cells.iter().flatten() {
+        match &mut span {
+            Some(s) if same_style(s, cell) => s.input.push_str(&cell.input),
+            _ => {
+                if let Some(s) = span.replace(cell.clone()) {
+                    output.push_str(&s.to_string());
+                }
+            }
+        }
+    }
+    if let Some(s) = span {
+        output.push_str(&s.to_string());
+    }
+
+    return output;
+}
+
+fn same_style(a: &ColoredString, b: &ColoredString) -> bool {
+    return a.fgcolor == b.fgcolor && a.bgcolor == b.bgcolor && a.style == b.style;
+}
+
+fn dim(s: &ColoredString, brightness: f32) -> ColoredString {
+    let fgcolor = color::scale(s.fgcolor.unwrap_or(Color::White), brightness);
+    let mut dimmed = s.clone().color(fgcolor);
+    if let Some(bgcolor) = s.bgcolor {
+        dimmed = dimmed.on_color(color::scale(bgcolor, brightness));
+    }
+
+    return dimmed;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FillFrame;
+
+    impl Frame for FillFrame {
+        fn update(&mut self, _screen_width: usize, _screen_height: usize) {}
+
+        fn get_content(&mut self, _x: usize, _y: usize) -> Content {
+            return Content::ColoredString { s: "#".white() };
+        }
+    }
+
+    // draws over everything until it panics on its second update
+    struct PanickingFrame {
+        updates: usize,
+    }
+
+    impl Frame for PanickingFrame {
+        fn update(&mut self, _screen_width: usize, _screen_height: usize) {
+            self.updates += 1;
+        }
+
+        fn get_content(&mut self, _x: usize, _y: usize) -> Content {
+            if self.updates > 1 {
+                panic!("deliberately broken frame");
+            }
+            return Content::ColoredString { s: "!".white() };
+        }
+    }
+
+    // draws on the top row only
+    struct TopRowFrame;
+
+    impl Frame for TopRowFrame {
+        fn update(&mut self, _screen_width: usize, _screen_height: usize) {}
+
+        fn get_content(&mut self, _x: usize, y: usize) -> Content {
+            if y > 0 {
+                return Content::Transparent;
+            }
+            return Content::ColoredString { s: "=".white() };
+        }
+    }
+
+    // would draw everywhere, but owns up to showing only the middle cell
+    struct BoundedFrame;
+
+    impl Frame for BoundedFrame {
+        fn update(&mut self, _screen_width: usize, _screen_height: usize) {}
+
+        fn content_bounds(&self) -> Option<Bounds> {
+            return Some(Bounds {
+                x: 1,
+                y: 1,
+                width: 1,
+                height: 1,
+            });
+        }
+
+        fn get_content(&mut self, _x: usize, _y: usize) -> Content {
+            return Content::ColoredString { s: "%".white() };
+        }
+    }
+
+    // a wide glyph on the top row at column `x`
+    struct WideFrame {
+        x: usize,
+    }
+
+    impl Frame for WideFrame {
+        fn update(&mut self, _screen_width: usize, _screen_height: usize) {}
+
+        fn get_content(&mut self, x: usize, y: usize) -> Content {
+            let glyph = crate::frame::string_to_content_vec("雪", Color::White);
+            return match (y, x.checked_sub(self.x)) {
+                (0, Some(column)) if column < glyph.len() => glyph[column].clone(),
+                _ => Content::Transparent,
+            };
+        }
+    }
+
+    // shows the given contents at the given cells and nothing elsewhere
+    struct ScriptedFrame {
+        cells: Vec<((usize, usize), Content)>,
+    }
+
+    impl ScriptedFrame {
+        fn top_row(contents: Vec<Content>) -> Self {
+            return ScriptedFrame {
+                cells: contents
+                    .into_iter()
+                    .enumerate()
+                    .map(|(x, content)| ((x, 0), content))
+                    .collect(),
+            };
+        }
+    }
+
+    impl Frame for ScriptedFrame {
+        fn update(&mut self, _screen_width: usize, _screen_height: usize) {}
+
+        fn get_content(&mut self, x: usize, y: usize) -> Content {
+            return self
+                .cells
+                .iter()
+                .find(|(cell, _)| *cell == (x, y))
+                .map_or(Content::Transparent, |(_, content)| content.clone());
+        }
+    }
+
+    fn glyph(s: &str) -> Content {
+        return Content::ColoredString { s: s.normal() };
+    }
+
+    fn printer() -> Printer {
+        return Printer::new(vec![
+            Box::new(PanickingFrame { updates: 0 }),
+            Box::new(FillFrame),
+        ]);
+    }
+
+    fn plain(printer: &mut Printer) -> Vec<String> {
+        return printer
+            .render_cells()
+            .iter()
+            .map(|row| {
+                return row
+                    .iter()
+                    .map(|cell| match cell {
+                        Cell::Glyph { s } => s.input.clone(),
+                        _ => " ".to_owned(),
+                    })
+                    .collect();
+            })
+            .collect();
+    }
+
+    #[test]
+    fn leaves_out_a_panicking_frame() {
+        let mut printer = printer();
+        printer.update_with_size(3, 2);
+        assert_eq!(plain(&mut printer), ["!!!", "!!!"]);
+
+        printer.update_with_size(3, 2);
+        assert_eq!(plain(&mut printer), ["###", "###"]);
+        printer.update_with_size(3, 2);
+        assert_eq!(plain(&mut printer), ["###", "###"]);
+    }
+
+    #[test]
+    fn shows_the_first_frame_drawing_a_cell() {
+        let mut printer = Printer::new(vec![Box::new(TopRowFrame), Box::new(FillFrame)]);
+        printer.update_with_size(3, 2);
+        assert_eq!(plain(&mut printer), ["===", "###"]);
+    }
+
+    #[test]
+    fn shows_the_last_frame_drawing_a_cell_when_reversed() {
+        let mut printer = Printer::new(vec![Box::new(TopRowFrame), Box::new(FillFrame)]);
+        printer.set_reversed(true);
+        printer.update_with_size(3, 2);
+        assert_eq!(plain(&mut printer), ["###", "###"]);
+
+        let mut printer = Printer::new(vec![Box::new(FillFrame), Box::new(TopRowFrame)]);
+        printer.set_reversed(true);
+        printer.update_with_size(3, 2);
+        assert_eq!(plain(&mut printer), ["===", "###"]);
+    }
+
+    #[test]
+    fn writes_only_changed_cells() {
+        let mut printer = printer();
+        let mut output = Vec::new();
+        printer.update_with_size(3, 1);
+        printer.write_changes_at(&mut output, 0, 0, true).unwrap();
+        assert!(output.starts_with(b"\x1b[H\x1b[2J\x1b[1;1H"));
+
+        // the same picture again needs nothing drawn
+        output.clear();
+        printer.write_changes_at(&mut output, 0, 0, true).unwrap();
+        assert!(output.is_empty());
+
+        // the panicking frame leaves every cell to the fill below it
+        output.clear();
+        printer.update_with_size(3, 1);
+        printer.write_changes_at(&mut output, 2, 5, true).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("\x1b[6;3H"));
+        assert_eq!(output.matches('#').count(), 3);
+    }
+
+    #[test]
+    fn accounts_for_both_columns_of_a_wide_glyph() {
+        for (x, expected) in [(0, "G-##"), (1, "#G-#"), (2, "##G-"), (3, "####")] {
+            let mut printer = Printer::new(vec![Box::new(WideFrame { x }), Box::new(FillFrame)]);
+            printer.update_with_size(4, 1);
+            let cells = printer.render_cells()[0]
+                .iter()
+                .map(|cell| match cell {
+                    Cell::Glyph { s } if s.input == "雪" => 'G',
+                    Cell::Glyph { s } => s.input.chars().next().unwrap(),
+                    Cell::Continuation => '-',
+                    Cell::Empty => ' ',
+                })
+                .collect::<String>();
+            assert_eq!(cells, expected, "wide glyph at column {}", x);
+
+            let lines = printer.render_lines();
+            assert_eq!(lines[0].width(), 4, "wide glyph at column {}", x);
+        }
+    }
+
+    #[test]
+    fn sees_through_transparent_and_compensate_contents() {
+        let mut printer = Printer::new(vec![
+            Box::new(ScriptedFrame::top_row(vec![
+                Content::Transparent,
+                Content::Compensate,
+                glyph("a"),
+            ])),
+            Box::new(ScriptedFrame::top_row(vec![
+                glyph("b"),
+                glyph("c"),
+                glyph("d"),
+            ])),
+        ]);
+        printer.update_with_size(4, 2);
+        let top_row = &printer.render_cells()[0];
+        assert!(matches!(&top_row[0], Cell::Glyph { s } if s.input == "b"));
+        assert!(matches!(&top_row[1], Cell::Glyph { s } if s.input == "c"));
+        assert!(matches!(&top_row[2], Cell::Glyph { s } if s.input == "a"));
+        assert!(matches!(top_row[3], Cell::Empty));
+        assert_eq!(printer.render_lines(), ["bca ", "    "]);
+    }
+
+    #[test]
+    fn highlights_cells_drawn_by_several_frames() {
+        let mut printer = Printer::new(vec![
+            Box::new(ScriptedFrame::top_row(vec![glyph("a"), glyph("b")])),
+            Box::new(ScriptedFrame::top_row(vec![glyph("c")])),
+        ]);
+        printer.set_debug_overlap(true);
+        printer.update_with_size(2, 1);
+        let top_row = &printer.render_cells()[0];
+        assert!(
+            matches!(&top_row[0], Cell::Glyph { s } if s.input == "a" && s.bgcolor == Some(Color::Magenta))
+        );
+        assert!(matches!(&top_row[1], Cell::Glyph { s } if s.bgcolor.is_none()));
+    }
+
+    #[test]
+    fn shows_what_is_under_a_hidden_frame() {
+        let mut printer = Printer::new(vec![
+            Box::new(ScriptedFrame::top_row(vec![glyph("a"), glyph("b")])),
+            Box::new(ScriptedFrame::top_row(vec![glyph("c")])),
+        ]);
+        printer.update_with_size(2, 1);
+        printer.set_hidden(0, true);
+        printer.set_hidden(2, true);
+        assert_eq!(printer.render_lines(), ["c "]);
+        printer.set_hidden(0, false);
+        assert_eq!(printer.render_lines(), ["ab"]);
+    }
+
+    #[test]
+    fn skips_the_column_covered_by_a_wide_glyph() {
+        let mut printer = Printer::new(vec![
+            Box::new(ScriptedFrame::top_row(vec![
+                glyph("雪"),
+                Content::Compensate,
+                Content::Transparent,
+            ])),
+            // never asked for its second column, hidden under the wide glyph
+            Box::new(ScriptedFrame::top_row(vec![
+                glyph("b"),
+                glyph("c"),
+                glyph("d"),
+            ])),
+        ]);
+        printer.update_with_size(3, 1);
+        let top_row = &printer.render_cells()[0];
+        assert!(matches!(&top_row[0], Cell::Glyph { s } if s.input == "雪"));
+        assert!(matches!(top_row[1], Cell::Continuation));
+        assert!(matches!(&top_row[2], Cell::Glyph { s } if s.input == "d"));
+        assert_eq!(printer.render_lines(), ["雪d"]);
+    }
+
+    #[test]
+    fn skips_frames_outside_their_bounds() {
+        let mut printer = Printer::new(vec![Box::new(BoundedFrame), Box::new(FillFrame)]);
+        printer.update_with_size(3, 3);
+        assert_eq!(plain(&mut printer), ["###", "#%#", "###"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "deliberately broken frame")]
+    fn lets_panics_through_when_asked_to() {
+        let mut printer = printer();
+        printer.set_catch_panics(false);
+        printer.update_with_size(3, 2);
+        printer.update_with_size(3, 2);
+        printer.render_cells();
+    }
+}
@@ -0,0 +1,107 @@
+use std::io::{self, Write};
+
+use colored::Color;
+use font8x8::{
+    UnicodeFonts, BASIC_FONTS, BLOCK_FONTS, BOX_FONTS, GREEK_FONTS, HIRAGANA_FONTS, LATIN_FONTS,
+    MISC_FONTS,
+};
+
+use crate::{color, printer::Cell, string_width::StringWidth};
+
+/// Width of a cell in pixels.
+pub const CELL_WIDTH: usize = 8;
+/// Height of a cell in pixels, twice its width like a terminal cell.
+pub const CELL_HEIGHT: usize = 16;
+
+/// An RGB picture, row by row from the top left.
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<[u8; 3]>,
+}
+
+impl Image {
+    /// Writes the image as a binary PPM, which needs no compression at all.
+    pub fn write_ppm(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        return out.write_all(&self.pixels.concat());
+    }
+
+    pub fn write_png(&self, out: &mut impl Write) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(out, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        return writer
+            .write_image_data(&self.pixels.concat())
+            .map_err(io::Error::other);
+    }
+
+    fn fill(&mut self, x: usize, y: usize, width: usize, height: usize, rgb: [u8; 3]) {
+        for row in y..y + height {
+            let start = row * self.width + x;
+            self.pixels[start..start + width].fill(rgb);
+        }
+    }
+}
+
+/// Draws a picture from `Printer::render_cells` in an 8 x 8 pixel font,
+/// stretched to `CELL_WIDTH` x `CELL_HEIGHT` pixels per cell, on black.
+/// Glyphs missing from the font, such as CJK characters, are drawn as boxes.
+pub fn rasterize(cells: &[Vec<Cell>]) -> Image {
+    let columns = cells.first().map_or(0, |row| row.len());
+    let mut image = Image {
+        width: columns * CELL_WIDTH,
+        height: cells.len() * CELL_HEIGHT,
+        pixels: vec![[0, 0, 0]; columns * CELL_WIDTH * cells.len() * CELL_HEIGHT],
+    };
+
+    for (y, row) in cells.iter().enumerate() {
+        for (x, cell) in row.iter().enumerate() {
+            let Cell::Glyph { s } = cell else {
+                continue;
+            };
+            let (left, top) = (x * CELL_WIDTH, y * CELL_HEIGHT);
+            let width = s.width().min(columns - x) * CELL_WIDTH;
+            if let Some(bgcolor) = s.bgcolor {
+                let (r, g, b) = color::to_rgb(bgcolor);
+                image.fill(left, top, width, CELL_HEIGHT, [r, g, b]);
+            }
+
+            let (r, g, b) = color::to_rgb(s.fgcolor.unwrap_or(Color::White));
+            let glyph = s.input.chars().next().unwrap_or(' ');
+            match font_glyph(glyph) {
+                Some(bitmap) => {
+                    for (row, bits) in bitmap.iter().enumerate() {
+                        for column in (0..8).filter(|column| bits & (1 << column) != 0) {
+                            image.fill(left + column, top + row * 2, 1, 2, [r, g, b]);
+                        }
+                    }
+                }
+                None => {
+                    image.fill(left + 1, top + 2, width - 2, 1, [r, g, b]);
+                    image.fill(left + 1, top + CELL_HEIGHT - 3, width - 2, 1, [r, g, b]);
+                    image.fill(left + 1, top + 2, 1, CELL_HEIGHT - 4, [r, g, b]);
+                    image.fill(left + width - 2, top + 2, 1, CELL_HEIGHT - 4, [r, g, b]);
+                }
+            }
+        }
+    }
+
+    return image;
+}
+
+fn font_glyph(c: char) -> Option<[u8; 8]> {
+    if c == ' ' {
+        return Some([0; 8]);
+    }
+
+    return BASIC_FONTS
+        .get(c)
+        .or_else(|| LATIN_FONTS.get(c))
+        .or_else(|| BOX_FONTS.get(c))
+        .or_else(|| BLOCK_FONTS.get(c))
+        .or_else(|| GREEK_FONTS.get(c))
+        .or_else(|| HIRAGANA_FONTS.get(c))
+        .or_else(|| MISC_FONTS.get(c));
+}
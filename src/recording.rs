@@ -0,0 +1,131 @@
+use std::{fs, time::Duration};
+
+use crate::json::{expect, parse_string, skip_space};
+
+/// Frames of terminal output to be played back as they are, each with the
+/// time to wait before showing the next one.
+pub struct Recording {
+    pub frames: Vec<(Vec<u8>, Duration)>,
+}
+
+impl Recording {
+    /// Loads the output events of an asciicast v2 recording, as written by
+    /// `asciinema rec`.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|error| format!("cannot read recording '{}': {}", path, error))?;
+        return Recording::parse(&text)
+            .map_err(|error| format!("invalid recording '{}': {}", path, error));
+    }
+
+    fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines().enumerate();
+        let header = lines.next().map_or("", |(_, line)| line);
+        let version = header
+            .split_once("\"version\"")
+            .and_then(|(_, rest)| rest.trim_start().strip_prefix(':'))
+            .map(|rest| rest.trim_start());
+        if !version.is_some_and(|version| version.starts_with('2')) {
+            return Err("line 1: expected an asciicast v2 header".to_owned());
+        }
+
+        let malformed = |i: usize| format!("line {}: malformed event", i + 1);
+        // (line, time since the start, output) of each output event
+        let mut events = Vec::new();
+        for (i, line) in lines {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (time, kind, data) = parse_event(line).ok_or_else(|| malformed(i))?;
+            if kind == "o" {
+                events.push((i, time, data));
+            }
+        }
+
+        let mut frames = Vec::with_capacity(events.len());
+        for (i, (_, time, data)) in events.iter().enumerate() {
+            // the last frame waits as long as the first before the loop starts over
+            let (line, next) = events
+                .get(i + 1)
+                .map_or((events[0].0, time + events[0].1), |(line, next, _)| {
+                    (*line, *next)
+                });
+            // a gap too long to wait out is as good as a broken time
+            let delay =
+                Duration::try_from_secs_f64((next - time).max(0.0)).map_err(|_| malformed(line))?;
+            frames.push((data.as_bytes().to_vec(), delay));
+        }
+
+        return Ok(Recording { frames });
+    }
+}
+
+// an event line looks like `[1.5, "o", "output"]`
+fn parse_event(line: &str) -> Option<(f64, String, String)> {
+    let rest = line.trim().strip_prefix('[')?;
+    let (time, rest) = rest.split_once(',')?;
+    // `parse` takes `inf` and `NaN` too, which are no times
+    let time = time
+        .trim()
+        .parse()
+        .ok()
+        .filter(|time: &f64| time.is_finite())?;
+    let mut chars = rest.chars().peekable();
+    skip_space(&mut chars);
+    let kind = parse_string(&mut chars).ok()?;
+    expect(&mut chars, ',').ok()?;
+    skip_space(&mut chars);
+    let data = parse_string(&mut chars).ok()?;
+    expect(&mut chars, ']').ok()?;
+    skip_space(&mut chars);
+    if chars.next().is_some() {
+        return None;
+    }
+
+    return Some((time, kind, data));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_output_events() {
+        let recording = Recording::parse(
+            "{\"version\": 2, \"width\": 80, \"height\": 24}\n\
+             [0.5, \"o\", \"\\u001b[H\\u2744 snow\\r\\n\"]\n\
+             [0.7, \"i\", \"q\"]\n\
+             [1.5, \"o\", \"\\ud83c\\udf84\"]\n",
+        )
+        .unwrap();
+
+        assert_eq!(recording.frames.len(), 2);
+        assert_eq!(recording.frames[0].0, "\x1b[H❄ snow\r\n".as_bytes());
+        assert_eq!(recording.frames[0].1, Duration::from_secs(1));
+        assert_eq!(recording.frames[1].0, "🎄".as_bytes());
+        assert_eq!(recording.frames[1].1, Duration::from_millis(500));
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        assert!(Recording::parse("{\"version\": 1}\n").is_err());
+    }
+
+    #[test]
+    fn reports_malformed_lines() {
+        let error = |text: &str| Recording::parse(text).err();
+        assert_eq!(
+            error("{\"version\": 2}\n[0.1, \"o\", \"x\"\n").as_deref(),
+            Some("line 2: malformed event")
+        );
+        // times that cannot be waited out
+        for event in ["[inf, \"o\", \"x\"]", "[1e20, \"o\", \"x\"]"] {
+            assert_eq!(
+                error(&format!("{{\"version\": 2}}\n\n{}\n", event)).as_deref(),
+                Some("line 3: malformed event"),
+                "{}",
+                event
+            );
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::sync::Mutex;
+
+use crate::{
+    frame::Frame,
+    frames::{
+        AuroraFrame, CandlesFrame, ChristmasTreeFrame, CloudsFrame, DebugFrame, FireworksFrame,
+        GarlandFrame, GiftsFrame, SantaSleighFrame, SnowFrame, SnowmanFrame, WindowFrame,
+    },
+};
+
+/// Makes a frame of some kind with its default settings, for adding frames
+/// by name, e.g. from a config file.
+pub type FrameFactory = fn() -> Box<dyn Frame>;
+
+// frames registered on top of the bundled ones, later ones winning
+static REGISTRY: Mutex<Vec<(String, FrameFactory)>> = Mutex::new(Vec::new());
+
+const BUNDLED: &[(&str, FrameFactory)] = &[
+    ("tree", || Box::<ChristmasTreeFrame>::default()),
+    ("snow", || Box::<SnowFrame>::default()),
+    ("garland", || Box::<GarlandFrame>::default()),
+    ("sleigh", || Box::<SantaSleighFrame>::default()),
+    ("candles", || Box::<CandlesFrame>::default()),
+    ("snowman", || Box::<SnowmanFrame>::default()),
+    ("gifts", || Box::<GiftsFrame>::default()),
+    ("aurora", || Box::<AuroraFrame>::default()),
+    ("clouds", || Box::<CloudsFrame>::default()),
+    ("window", || Box::<WindowFrame>::default()),
+    ("fireworks", || Box::<FireworksFrame>::default()),
+    ("debug", || Box::<DebugFrame>::default()),
+];
+
+/// Makes frames of another kind available by `name`, replacing whatever
+/// went by that name before, bundled frames included.
+///
+/// ```
+/// use cyber_christmas_card::{create_frame, register_frame, Content, Frame};
+///
+/// // a lone star at the top of the screen
+/// struct StarFrame {
+///     frame_width: usize,
+/// }
+///
+/// impl Frame for StarFrame {
+///     fn update(&mut self, screen_width: usize, _screen_height: usize) {
+///         self.frame_width = screen_width;
+///     }
+///
+///     fn get_content(&mut self, x: usize, y: usize) -> Content {
+///         if (x, y) != (self.frame_width / 2, 0) {
+///             return Content::Transparent;
+///         }
+///         return Content::ColoredString { s: "*".into() };
+///     }
+/// }
+///
+/// register_frame("star", || Box::new(StarFrame { frame_width: 0 }));
+/// assert!(create_frame("star").is_some());
+/// ```
+pub fn register_frame(name: &str, factory: FrameFactory) {
+    let mut registry = REGISTRY.lock().unwrap_or_else(|error| error.into_inner());
+    registry.retain(|(registered, _)| registered != name);
+    registry.push((name.to_owned(), factory));
+}
+
+/// A new frame of the kind registered as `name`, if there is one.
+pub fn create_frame(name: &str) -> Option<Box<dyn Frame>> {
+    let registry = REGISTRY.lock().unwrap_or_else(|error| error.into_inner());
+    let factory = registry
+        .iter()
+        .find(|(registered, _)| registered == name)
+        .map(|(_, factory)| *factory)
+        .or_else(|| {
+            return BUNDLED
+                .iter()
+                .find(|(bundled, _)| *bundled == name)
+                .map(|(_, factory)| *factory);
+        })?;
+    drop(registry);
+
+    return Some(factory());
+}
+
+/// Names frames can be made by, the bundled ones first.
+pub fn frame_names() -> Vec<String> {
+    let registry = REGISTRY.lock().unwrap_or_else(|error| error.into_inner());
+    let mut names = BUNDLED
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .collect::<Vec<String>>();
+    for (name, _) in registry.iter() {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+
+    return names;
+}
@@ -0,0 +1,222 @@
+use std::{ops::Range, time::Duration};
+
+use bit_set::BitSet;
+use colored::Color;
+
+/// State shared by every frame, rebuilt by the printer on each update.
+#[derive(Default)]
+pub struct Scene {
+    width: usize,
+    height: usize,
+    /// Horizontal wind in columns per update, positive blowing to the right.
+    pub wind: isize,
+    /// Columns the scene scrolls by on each update, zero keeping it still.
+    pub scroll_speed: f32,
+    /// Columns the scene has scrolled by so far.
+    pub scroll: f32,
+    /// Width of the world frames with a virtual width are laid out over,
+    /// never narrower than the screen.
+    pub virtual_width: usize,
+    /// Columns the camera pans by on each update, negative panning left.
+    pub pan_speed: f32,
+    /// Column of the world the camera has panned to.
+    pub camera: f32,
+    /// Time the card has been showing for, advanced by whoever drives the
+    /// printer so timed sequences keep to the clock they are shown at.
+    pub time: Duration,
+    /// Tempo of the music the card is dancing to, in beats per minute.
+    pub bpm: Option<f32>,
+    /// How thickly snow falls compared to usual, e.g. `2.0` for twice the
+    /// flakes. Snow eases towards it rather than changing all at once.
+    pub snowfall: Option<f32>,
+    /// Scales whatever snowfall is set, e.g. to thin the snow out while the
+    /// card is slow to draw. None leaves it as it is.
+    pub snowfall_scale: Option<f32>,
+    /// Shown in place of the blessing while set, `{year}` being the year,
+    /// e.g. by a program driving the card.
+    pub message: Option<String>,
+    events: Vec<Event>,
+    solid_rows: Vec<BitSet>,
+    surface_rows: Vec<BitSet>,
+    ledges: Vec<Ledge>,
+    clouds: Vec<Range<usize>>,
+    lights: Vec<Light>,
+    snow_depths: Vec<usize>,
+}
+
+/// A source of light, brightening the cells within `radius` columns of it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Light {
+    pub x: usize,
+    pub y: usize,
+    pub radius: f32,
+    pub color: Color,
+}
+
+impl Light {
+    /// How lit the cell is, from `1.0` at the light to `0.0` at its radius.
+    /// Rows count double as terminal cells are about twice as tall as wide.
+    pub fn intensity_at(&self, x: usize, y: usize) -> f32 {
+        let dx = x as f32 - self.x as f32;
+        let dy = (y as f32 - self.y as f32) * 2.0;
+        return (1.0 - (dx * dx + dy * dy).sqrt() / self.radius).max(0.0);
+    }
+}
+
+/// Something that happened in the scene, reported by one frame for others
+/// to react to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// A countdown reached midnight.
+    Midnight,
+}
+
+/// A horizontal run of cells whose top edge snow can settle on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Surface {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+}
+
+/// A surface snow piles up on in a ledge up to `height` rows deep, rather
+/// than just capping it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ledge {
+    pub surface: Surface,
+    pub height: usize,
+}
+
+impl Scene {
+    pub fn width(&self) -> usize {
+        return self.width;
+    }
+
+    pub fn height(&self) -> usize {
+        return self.height;
+    }
+
+    /// Width of the world, the screen being a window onto part of it.
+    pub fn world_width(&self) -> usize {
+        return self.virtual_width.max(self.width);
+    }
+
+    /// Column of the world shown at the left edge of the screen.
+    pub fn camera_x(&self) -> usize {
+        let world_width = self.world_width().max(1) as isize;
+        return (self.camera.round() as isize).rem_euclid(world_width) as usize;
+    }
+
+    /// Beats since the card started, the fraction being the phase within the
+    /// current beat. None unless a tempo is set.
+    pub fn beat(&self) -> Option<f32> {
+        return self.bpm.map(|bpm| self.time.as_secs_f32() * bpm / 60.0);
+    }
+
+    /// How thickly snow falls compared to usual once scaled.
+    pub fn scaled_snowfall(&self) -> f32 {
+        return self.snowfall.unwrap_or(1.0) * self.snowfall_scale.unwrap_or(1.0);
+    }
+
+    /// Whether any frame reported the cell as solid. Out of bounds cells are not.
+    pub fn is_solid(&self, x: usize, y: usize) -> bool {
+        return self
+            .solid_rows
+            .get(y)
+            .is_some_and(|solids| solids.contains(x));
+    }
+
+    /// Whether a frame reported the top edge of a surface at the cell.
+    pub fn is_surface(&self, x: usize, y: usize) -> bool {
+        return self
+            .surface_rows
+            .get(y)
+            .is_some_and(|surfaces| surfaces.contains(x));
+    }
+
+    /// Lights reported by the frames.
+    pub fn lights(&self) -> &[Light] {
+        return &self.lights;
+    }
+
+    /// Rows deep snow may pile up on the cell, the top edge of a surface: 1
+    /// on most surfaces, as many as the deepest ledge there allows on a
+    /// ledge, and none off surfaces.
+    pub fn ledge_height(&self, x: usize, y: usize) -> usize {
+        if !self.is_surface(x, y) {
+            return 0;
+        }
+        return self
+            .ledges
+            .iter()
+            .filter(|ledge| {
+                let surface = ledge.surface;
+                return surface.y == y && x >= surface.x && x < surface.x + surface.width;
+            })
+            .map(|ledge| ledge.height)
+            .fold(1, usize::max);
+    }
+
+    /// Columns each cloud in the sky hangs over, as of the last update.
+    pub fn clouds(&self) -> &[Range<usize>] {
+        return &self.clouds;
+    }
+
+    /// Depth of the ground snow on column `x`, the deepest any frame reported.
+    pub fn snow_depth(&self, x: usize) -> usize {
+        return self.snow_depths.get(x).copied().unwrap_or(0);
+    }
+
+    /// Events reported by the frames on this update.
+    pub fn events(&self) -> &[Event] {
+        return &self.events;
+    }
+
+    pub(crate) fn reset(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+        self.solid_rows = vec![BitSet::with_capacity(width); height];
+        self.surface_rows = vec![BitSet::with_capacity(width); height];
+        self.ledges.clear();
+        self.clouds.clear();
+        self.lights.clear();
+        self.events.clear();
+        self.snow_depths = vec![0; width];
+    }
+
+    pub(crate) fn add_snow_depths(&mut self, snow_depths: &[usize]) {
+        for (depth, reported) in self.snow_depths.iter_mut().zip(snow_depths) {
+            *depth = (*depth).max(*reported);
+        }
+    }
+
+    pub(crate) fn add_event(&mut self, event: Event) {
+        self.events.push(event);
+    }
+
+    pub(crate) fn set_solid(&mut self, x: usize, y: usize) {
+        self.solid_rows[y].insert(x);
+    }
+
+    pub(crate) fn add_light(&mut self, light: Light) {
+        self.lights.push(light);
+    }
+
+    pub(crate) fn add_cloud(&mut self, columns: Range<usize>) {
+        self.clouds.push(columns);
+    }
+
+    pub(crate) fn add_ledge(&mut self, ledge: &Ledge) {
+        self.add_surface(&ledge.surface);
+        self.ledges.push(*ledge);
+    }
+
+    pub(crate) fn add_surface(&mut self, surface: &Surface) {
+        let Some(surfaces) = self.surface_rows.get_mut(surface.y) else {
+            return;
+        };
+        for x in surface.x..(surface.x + surface.width).min(self.width) {
+            surfaces.insert(x);
+        }
+    }
+}
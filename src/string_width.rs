@@ -0,0 +1,46 @@
+use colored::ColoredString;
+
+/// Number of terminal columns a string occupies.
+pub trait StringWidth {
+    fn width(&self) -> usize;
+}
+
+impl StringWidth for str {
+    fn width(&self) -> usize {
+        return self.chars().map(char_width).sum();
+    }
+}
+
+impl StringWidth for String {
+    fn width(&self) -> usize {
+        return self.as_str().width();
+    }
+}
+
+impl StringWidth for ColoredString {
+    fn width(&self) -> usize {
+        return self.input.width();
+    }
+}
+
+/// Number of terminal columns a character occupies: two for the wide east
+/// asian scripts and emoji, one for everything else.
+pub fn char_width(c: char) -> usize {
+    return match c as u32 {
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    };
+}
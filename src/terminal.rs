@@ -0,0 +1,139 @@
+use std::{
+    cell::Cell,
+    io::{self, IsTerminal, Write},
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    cursor,
+    event::{
+        self, DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture,
+        Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers,
+    },
+    execute, terminal,
+};
+
+/// Puts the terminal into raw mode with a hidden cursor so single key
+/// presses can be read, restoring it when dropped.
+///
+/// Without a terminal (e.g. stdout piped to a file) the terminal is left
+/// alone, there is nothing to read keys from and waiting simply sleeps.
+pub struct Session {
+    raw: bool,
+    titled: bool,
+    // any key press or mouse activity quits, like a screensaver
+    kiosk: bool,
+    track_focus: bool,
+    // whether the terminal has focus, as of its last report
+    focused: Cell<Option<bool>>,
+}
+
+impl Session {
+    /// Enters the session, also setting the window title to `title` unless it
+    /// is empty or stdout is not a terminal. In `kiosk` mode any key press or
+    /// mouse activity counts as asking to quit. With `track_focus` the
+    /// terminal is asked to report gaining and losing focus.
+    pub fn enter(title: &str, kiosk: bool, track_focus: bool) -> Self {
+        let raw = io::stdout().is_terminal() && terminal::enable_raw_mode().is_ok();
+        if raw {
+            let _ = execute!(io::stdout(), cursor::Hide);
+        }
+        if raw && kiosk {
+            let _ = execute!(io::stdout(), EnableMouseCapture);
+        }
+        let track_focus = raw && track_focus;
+        if track_focus {
+            let _ = execute!(io::stdout(), EnableFocusChange);
+        }
+
+        let titled = !title.is_empty() && io::stdout().is_terminal();
+        if titled {
+            // save the current title on the terminal's title stack first
+            print!("\x1b[22;0t\x1b]0;{}\x07", title);
+            let _ = io::stdout().flush();
+        }
+
+        return Session {
+            raw,
+            titled,
+            kiosk,
+            track_focus,
+            focused: Cell::new(None),
+        };
+    }
+
+    /// Whether the terminal has focus, none until it says so, which terminals
+    /// without focus reporting never do.
+    pub fn focused(&self) -> Option<bool> {
+        return self.focused.get();
+    }
+
+    /// Waits for `timeout`, returning early with `true` if the user asked to quit.
+    pub fn wait_for_quit(&self, timeout: Duration) -> bool {
+        if !self.raw {
+            std::thread::sleep(timeout);
+            return false;
+        }
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if !event::poll(remaining).unwrap_or(false) {
+                return false;
+            }
+
+            match event::read() {
+                Ok(Event::Key(key)) if is_quit_key(&key) => return true,
+                Ok(Event::Key(key)) if self.kiosk && key.kind == KeyEventKind::Press => {
+                    return true;
+                }
+                Ok(Event::Mouse(_)) if self.kiosk => return true,
+                Ok(Event::FocusGained) => self.focused.set(Some(true)),
+                Ok(Event::FocusLost) => self.focused.set(Some(false)),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        if self.raw && self.kiosk {
+            let _ = execute!(io::stdout(), DisableMouseCapture);
+        }
+        if self.track_focus {
+            let _ = execute!(io::stdout(), DisableFocusChange);
+        }
+        if self.raw {
+            let _ = execute!(io::stdout(), cursor::Show);
+            let _ = terminal::disable_raw_mode();
+        }
+        if self.titled {
+            // clear the title, then restore the saved one where the terminal supports it
+            print!("\x1b]0;\x07\x1b[23;0t");
+        }
+        let _ = io::stdout().flush();
+    }
+}
+
+/// Whether the terminal acts on escape codes. The first time it is asked on
+/// Windows it switches the console over to virtual terminal processing, which
+/// consoles older than Windows 10 cannot do.
+pub fn ansi_supported() -> bool {
+    #[cfg(windows)]
+    return crossterm::ansi_support::supports_ansi();
+    #[cfg(not(windows))]
+    return true;
+}
+
+fn is_quit_key(key: &KeyEvent) -> bool {
+    if key.kind != KeyEventKind::Press {
+        return false;
+    }
+
+    return match key.code {
+        KeyCode::Char('q') | KeyCode::Esc => true,
+        KeyCode::Char('c') => key.modifiers.contains(KeyModifiers::CONTROL),
+        _ => false,
+    };
+}
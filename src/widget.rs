@@ -0,0 +1,71 @@
+use colored::Color;
+use ratatui::{buffer::Buffer, layout::Rect, style, widgets::StatefulWidget};
+
+use crate::printer::{Cell, Printer};
+
+/// Draws a [`Printer`] into a ratatui area.
+///
+/// Every render sizes the printer to the area and advances the animation by
+/// one step, so draw it at the card's own pace (about once a second) rather
+/// than on every redraw of the host application. Empty cells are left
+/// untouched, letting whatever is beneath the area show through.
+///
+/// ```ignore
+/// terminal.draw(|f| f.render_stateful_widget(CardWidget, area, &mut printer))?;
+/// ```
+pub struct CardWidget;
+
+impl StatefulWidget for CardWidget {
+    type State = Printer;
+
+    fn render(self, area: Rect, buf: &mut Buffer, printer: &mut Printer) {
+        printer.update_with_size(area.width as usize, area.height as usize);
+
+        for (y, row) in printer.render_cells().into_iter().enumerate() {
+            for (x, cell) in row.into_iter().enumerate() {
+                let position = (area.x + x as u16, area.y + y as u16);
+                let Some(buffer_cell) = buf.cell_mut(position) else {
+                    continue;
+                };
+
+                match cell {
+                    Cell::Empty => {}
+                    Cell::Glyph { s } => {
+                        buffer_cell.set_symbol(&s.input);
+                        if let Some(color) = s.fgcolor {
+                            buffer_cell.set_fg(to_ratatui_color(color));
+                        }
+                        if let Some(color) = s.bgcolor {
+                            buffer_cell.set_bg(to_ratatui_color(color));
+                        }
+                    }
+                    Cell::Continuation => {
+                        buffer_cell.reset();
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn to_ratatui_color(color: Color) -> style::Color {
+    return match color {
+        Color::Black => style::Color::Black,
+        Color::Red => style::Color::Red,
+        Color::Green => style::Color::Green,
+        Color::Yellow => style::Color::Yellow,
+        Color::Blue => style::Color::Blue,
+        Color::Magenta => style::Color::Magenta,
+        Color::Cyan => style::Color::Cyan,
+        Color::White => style::Color::Gray,
+        Color::BrightBlack => style::Color::DarkGray,
+        Color::BrightRed => style::Color::LightRed,
+        Color::BrightGreen => style::Color::LightGreen,
+        Color::BrightYellow => style::Color::LightYellow,
+        Color::BrightBlue => style::Color::LightBlue,
+        Color::BrightMagenta => style::Color::LightMagenta,
+        Color::BrightCyan => style::Color::LightCyan,
+        Color::BrightWhite => style::Color::White,
+        Color::TrueColor { r, g, b } => style::Color::Rgb(r, g, b),
+    };
+}
@@ -0,0 +1,53 @@
+//! Measures how much less the diff renderer writes than a full repaint once
+//! the card has settled. Run it with `cargo test --test output_size --
+//! --nocapture` to see the ratio.
+
+#![allow(clippy::needless_return)]
+
+use cyber_christmas_card::{ChristmasTreeFrame, Printer, SnowFrame};
+
+const WIDTH: usize = 80;
+const HEIGHT: usize = 24;
+// frames drawn before measuring, for the snow to fill the sky
+const WARM_UP: usize = 20;
+const FRAMES: usize = 50;
+const LIGHT_SNOWFALL: f32 = 0.3;
+// the diff renderer must write under this fraction of a full repaint, one
+// repainting everything writing about as much as a full frame or more
+const MAX_RATIO: f64 = 0.5;
+
+#[test]
+fn writes_far_less_than_a_full_repaint_in_steady_state() {
+    colored::control::set_override(true);
+    let mut printer = Printer::new(vec![
+        Box::new(ChristmasTreeFrame::default().with_seed(2024)),
+        Box::new(SnowFrame::default().with_seed(2025)),
+    ]);
+    printer.scene_mut().snowfall = Some(LIGHT_SNOWFALL);
+
+    let (mut full, mut diff) = (0, 0);
+    for frame in 0..WARM_UP + FRAMES {
+        printer.update_with_size(WIDTH, HEIGHT);
+        let mut repaint = Vec::new();
+        printer.write_at(&mut repaint, 0, 0).unwrap();
+        let mut changes = Vec::new();
+        printer.write_changes_at(&mut changes, 0, 0, false).unwrap();
+        if frame >= WARM_UP {
+            full += repaint.len();
+            diff += changes.len();
+        }
+    }
+
+    let ratio = diff as f64 / full as f64;
+    println!(
+        "bytes per frame: {} diffed, {} repainted, {:.1}% of a full frame",
+        diff / FRAMES,
+        full / FRAMES,
+        ratio * 100.0
+    );
+    assert!(
+        ratio < MAX_RATIO,
+        "the diff renderer wrote {:.1}% of a full repaint",
+        ratio * 100.0
+    );
+}
@@ -0,0 +1,187 @@
+#![allow(clippy::needless_return)]
+
+use std::process::Command;
+
+const WIDTH: usize = 60;
+const HEIGHT: usize = 20;
+const FRAMES: usize = 5;
+const CLEAR: &str = "\x1b[H\x1b[2J";
+
+fn run_headless(extra_args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_cyber-christmas-card"))
+        .args(["--size", &format!("{}x{}", WIDTH, HEIGHT)])
+        .args(["--frames", &FRAMES.to_string(), "--seed", "2024"])
+        .args(["--interval", "0", "--no-fade"])
+        // frames are told apart by the clear before each
+        .args(["--render-mode", "clear"])
+        .args(extra_args)
+        .env("CLICOLOR_FORCE", "1")
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    return String::from_utf8(output.stdout).unwrap();
+}
+
+/// Runs the card expecting it to refuse the options, returning what it said.
+fn run_invalid(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_cyber-christmas-card"))
+        .args(args)
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(2), "accepted {:?}", args);
+    return String::from_utf8(output.stderr).unwrap();
+}
+
+/// Checks every escape is a complete CSI (`ESC [ params final`) or OSC
+/// (`ESC ] text BEL`) sequence, and strips them leaving the visible text.
+fn strip_ansi(s: &str) -> String {
+    let mut visible = String::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            visible.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('[') => loop {
+                match chars.next() {
+                    Some('0'..='9' | ';' | '?') => {}
+                    Some('@'..='~') => break,
+                    other => panic!("malformed CSI sequence ending in {:?}", other),
+                }
+            },
+            Some(']') => {
+                assert!(chars.any(|c| c == '\x07'), "unterminated OSC sequence");
+            }
+            other => panic!("unexpected escape followed by {:?}", other),
+        }
+    }
+
+    return visible;
+}
+
+#[test]
+fn renders_every_frame_headless() {
+    let output = run_headless(&[]);
+    let frames = output.split(CLEAR).skip(1).collect::<Vec<&str>>();
+
+    // one clear per frame plus the final one on exit
+    assert_eq!(frames.len(), FRAMES + 1);
+    for frame in &frames[..FRAMES] {
+        assert!(frame.contains("\x1b["), "frame has no colors");
+
+        let visible = strip_ansi(frame);
+        let rows = visible.split("\r\n").collect::<Vec<&str>>();
+        assert_eq!(rows.len(), HEIGHT);
+        assert!(rows.iter().any(|row| row.contains('*')), "tree is missing");
+    }
+    assert_eq!(frames[FRAMES], "");
+}
+
+#[test]
+fn is_reproducible_with_a_seed() {
+    assert_eq!(run_headless(&[]), run_headless(&[]));
+}
+
+#[test]
+fn shares_escapes_between_cells_of_the_same_color() {
+    // a bare tree's widest row is an unbroken run of green leaves
+    let output = run_headless(&["--ornament-density", "0"]);
+    assert!(output.contains(&format!("\x1b[32m{}\x1b[0m", "*".repeat(19))));
+}
+
+#[test]
+fn prefers_the_numeric_seed_to_the_seed_text() {
+    assert_eq!(run_headless(&["--seed-text", "frosty"]), run_headless(&[]));
+}
+
+#[test]
+fn renders_every_feature_headless() {
+    let output = run_headless(&[
+        "--drift",
+        "--wind",
+        "1",
+        "--snow-caps",
+        "--reflections",
+        "--sleigh",
+        "--candles",
+        "--vignette",
+        "0.5",
+        "--scroll-speed",
+        "3",
+        "--parallax",
+        "snow=0.5",
+        "--parallax=tree=-1",
+        "--garland",
+        "--debug",
+        "--snow-speed",
+        "0.5-2",
+        "--pan",
+        "2",
+        "--pan-direction",
+        "left",
+        "--world-width",
+        "150",
+    ]);
+    strip_ansi(&output);
+    assert_eq!(output.matches(CLEAR).count(), FRAMES + 1);
+}
+
+#[test]
+fn draws_only_changes_in_diff_mode() {
+    let output = run_headless(&["--render-mode", "diff"]);
+    let cleared = run_headless(&[]);
+
+    // a clear before the first frame and the final one on exit
+    assert_eq!(output.matches(CLEAR).count(), 2);
+    assert!(output.len() < cleared.len());
+    strip_ansi(&output);
+}
+
+#[test]
+fn loops_precomputed_frames() {
+    let output = run_headless(&["--precompute", "2"]);
+    let frames = output.split(CLEAR).skip(1).collect::<Vec<&str>>();
+
+    assert_eq!(frames.len(), FRAMES + 1);
+    assert_ne!(frames[0], frames[1]);
+    assert_eq!(frames[0], frames[2]);
+    assert_eq!(frames[1], frames[3]);
+}
+
+#[test]
+fn checks_the_options_without_drawing_on_a_dry_run() {
+    let output = run_headless(&["--dry-run", "--candles"]);
+    assert!(!output.contains('\x1b'), "drew something: {:?}", output);
+    assert_eq!(output, "ok: 3 layers built, tree, candles, snow\n");
+}
+
+#[test]
+fn explains_why_a_value_is_invalid() {
+    assert_eq!(
+        run_invalid(&["--size", "0x5"]),
+        "error: invalid value '0x5' for '--size': the size must not be empty\n"
+    );
+    assert!(run_invalid(&["--region", "1,2,0,3"]).ends_with(": the region must not be empty\n"));
+}
+
+#[test]
+fn names_the_layers_a_parallax_can_be_set_for() {
+    assert!(run_invalid(&["--parallax", "bogus=1"]).ends_with(
+        ": unknown layer 'bogus', expected one of \
+         tree, garland, sleigh, candles, snowman, gifts, snow, aurora, clouds\n"
+    ));
+}
+
+#[test]
+fn names_the_steps_a_downgrade_can_take() {
+    assert!(run_invalid(&["--downgrade", "bogus"])
+        .contains(": unknown step 'bogus', expected vignette, snowfall or one of tree, "));
+}